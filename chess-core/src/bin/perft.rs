@@ -0,0 +1,42 @@
+use std::process::exit;
+
+use chess_core::perft::{perft, perft_divide};
+use chess_core::{ArrayBoard, START_FEN};
+
+const USAGE: &str = "usage: perft <depth> [--divide] [FEN]";
+
+// cargo run --release -p chess-core --bin perft -- 5 --divide "<FEN>"
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(depth) = args.first().and_then(|depth| depth.parse::<u32>().ok()) else {
+        eprintln!("{USAGE}");
+        exit(1);
+    };
+    let divide = args.iter().any(|arg| arg == "--divide");
+    let fen_parts: Vec<&str> = args[1..]
+        .iter()
+        .filter(|arg| *arg != "--divide")
+        .map(String::as_str)
+        .collect();
+    let fen = if fen_parts.is_empty() { START_FEN.to_string() } else { fen_parts.join(" ") };
+
+    let array_board = match ArrayBoard::from_fen(&fen) {
+        Ok(array_board) => array_board,
+        Err(error) => {
+            eprintln!("invalid FEN \"{fen}\": {error}");
+            exit(1);
+        }
+    };
+
+    if divide {
+        let mut total = 0;
+        for (mv, nodes) in perft_divide(&array_board, depth) {
+            println!("{mv}: {nodes}");
+            total += nodes;
+        }
+        println!();
+        println!("Nodes searched: {total}");
+    } else {
+        println!("{}", perft(&array_board, depth));
+    }
+}
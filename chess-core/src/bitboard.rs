@@ -0,0 +1,122 @@
+use crate::structs::{PieceColour, Square};
+
+// One bit per square, bit 0 is a1 and bit 63 is h8 (the same numbering as Square)
+pub type Bitboard = u64;
+
+const KNIGHT_DELTAS: [(i8, i8); 8] = [(2, 1), (2, -1), (1, 2), (1, -2), (-2, 1), (-2, -1), (-1, 2), (-1, -2)];
+const KING_DELTAS: [(i8, i8); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+// Ray directions, the first four run towards higher square numbers and the last four towards lower ones
+const NORTH: usize = 0;
+const EAST: usize = 1;
+const NORTH_EAST: usize = 2;
+const NORTH_WEST: usize = 3;
+const SOUTH: usize = 4;
+const WEST: usize = 5;
+const SOUTH_WEST: usize = 6;
+const SOUTH_EAST: usize = 7;
+const RAY_DELTAS: [(i8, i8); 8] = [(0, 1), (1, 0), (1, 1), (-1, 1), (0, -1), (-1, 0), (-1, -1), (1, -1)];
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = step_attacks(&KNIGHT_DELTAS);
+const KING_ATTACKS: [Bitboard; 64] = step_attacks(&KING_DELTAS);
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [step_attacks(&[(-1, 1), (1, 1)]), step_attacks(&[(-1, -1), (1, -1)])];
+const RAYS: [[Bitboard; 64]; 8] = rays();
+
+// The squares a piece moving by one of `deltas` can reach from each square
+const fn step_attacks(deltas: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut i = 0;
+        while i < deltas.len() {
+            let (file, rank) = ((square % 8) as i8 + deltas[i].0, (square / 8) as i8 + deltas[i].1);
+            if file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                table[square] |= 1 << (rank * 8 + file);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+// Every square from each square to the edge of the board in each direction, not including the start
+const fn rays() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut direction = 0;
+    while direction < 8 {
+        let (file_delta, rank_delta) = RAY_DELTAS[direction];
+        let mut square = 0;
+        while square < 64 {
+            let (mut file, mut rank) = ((square % 8) as i8 + file_delta, (square / 8) as i8 + rank_delta);
+            while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                table[direction][square] |= 1 << (rank * 8 + file);
+                file += file_delta;
+                rank += rank_delta;
+            }
+            square += 1;
+        }
+        direction += 1;
+    }
+    table
+}
+
+// The ray up to and including the first occupied square
+fn ray_attacks(square: Square, direction: usize, occupied: Bitboard) -> Bitboard {
+    let ray = RAYS[direction][square.index()];
+    let blockers = ray & occupied;
+    if blockers == 0 {
+        return ray;
+    }
+    let first_blocker = if direction < SOUTH {
+        blockers.trailing_zeros()
+    } else {
+        63 - blockers.leading_zeros()
+    };
+    ray ^ RAYS[direction][first_blocker as usize]
+}
+
+pub fn bit(square: Square) -> Bitboard {
+    1 << square.index()
+}
+
+// The squares of every set bit, from a1 upwards
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = Square> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let index = bitboard.trailing_zeros();
+        bitboard &= bitboard - 1;
+        Some(Square::from_index(index as usize))
+    })
+}
+
+pub fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square.index()]
+}
+
+pub fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+// The two diagonal squares a pawn of this colour captures on
+pub fn pawn_attacks(colour: &PieceColour, square: Square) -> Bitboard {
+    PAWN_ATTACKS[colour.index()][square.index()]
+}
+
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    [NORTH, EAST, SOUTH, WEST]
+        .iter()
+        .fold(0, |attacks, &direction| attacks | ray_attacks(square, direction, occupied))
+}
+
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    [NORTH_EAST, NORTH_WEST, SOUTH_WEST, SOUTH_EAST]
+        .iter()
+        .fold(0, |attacks, &direction| attacks | ray_attacks(square, direction, occupied))
+}
+
+pub fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
@@ -0,0 +1,257 @@
+use crate::bitboard::{bit, squares, Bitboard};
+use crate::fen::START_FEN;
+use crate::movegen::{castling_rook_tiles, is_in_check};
+use crate::structs::{CastlingRights, Move, Piece, PieceColour, PieceType, Square, UndoInfo};
+use crate::zobrist::{piece_key, state_key};
+
+#[derive(Clone, Debug)]
+pub struct ArrayBoard {
+    pub turn : PieceColour,
+    pub in_check : Option<PieceColour>,
+    pub en_passant : Option<Square>,
+    pub castling_rights : CastlingRights,
+    // halfmoves since the last capture or pawn move, for the fifty-move rule
+    pub halfmove_clock : u32,
+    // starts at 1 and goes up after every black move
+    pub fullmove_number : u32,
+    // the zobrist key of every position reached so far (including the current one), for repetitions
+    pub history : Vec<u64>,
+    // one bitboard per colour and piece type, indexed as [colour.index()][piece_type.index()]
+    // and kept in step with `occupancy` by set_piece, so always change pieces through it
+    bitboards : [[Bitboard; 6]; 2],
+    // every square each colour stands on
+    occupancy : [Bitboard; 2],
+    // the piece placement part of the zobrist key, updated by set_piece as pieces come and go
+    placement_key : u64,
+}
+
+impl ArrayBoard {
+    pub fn new() -> Self {
+        ArrayBoard::from_fen(START_FEN).expect("the start position is valid FEN")
+    }
+    // A board with no pieces, white to move and no castling rights
+    pub fn empty() -> Self {
+        ArrayBoard {
+            turn: PieceColour::White,
+            in_check: None,
+            en_passant: None,
+            castling_rights: CastlingRights::none(),
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            history: vec![],
+            bitboards: [[0; 6]; 2],
+            occupancy: [0; 2],
+            placement_key: 0,
+        }
+    }
+    pub fn swap_turn(&mut self) {
+        if self.turn.is_white() {
+            self.turn = PieceColour::Black;
+        } else {
+            self.turn = PieceColour::White;
+        } 
+    }
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        let colour = if self.occupancy[0] & bit(square) != 0 {
+            PieceColour::White
+        } else if self.occupancy[1] & bit(square) != 0 {
+            PieceColour::Black
+        } else {
+            return None;
+        };
+        PieceType::ALL
+            .into_iter()
+            .find(|piece_type| self.bitboards[colour.index()][piece_type.index()] & bit(square) != 0)
+            .map(|piece_type| Piece::new(colour, piece_type))
+    }
+    pub fn set_piece(&mut self, square: Square, piece: Option<Piece>) {
+        if let Some(old) = self.piece_at(square) {
+            self.bitboards[old.colour.index()][old.piece_type.index()] &= !bit(square);
+            self.occupancy[old.colour.index()] &= !bit(square);
+            self.placement_key ^= piece_key(&old, square);
+        }
+        if let Some(new) = piece {
+            self.bitboards[new.colour.index()][new.piece_type.index()] |= bit(square);
+            self.occupancy[new.colour.index()] |= bit(square);
+            self.placement_key ^= piece_key(&new, square);
+        }
+    }
+    fn take_piece(&mut self, square: Square) -> Option<Piece> {
+        let piece = self.piece_at(square);
+        self.set_piece(square, None);
+        piece
+    }
+    // Every occupied square and the piece standing on it
+    pub fn pieces(&self) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        squares(self.occupied()).filter_map(|square| self.piece_at(square).map(|piece| (square, piece)))
+    }
+    // The squares holding this colour's pieces of this type
+    pub fn pieces_of(&self, colour: &PieceColour, piece_type: PieceType) -> Bitboard {
+        self.bitboards[colour.index()][piece_type.index()]
+    }
+    pub fn colour_occupancy(&self, colour: &PieceColour) -> Bitboard {
+        self.occupancy[colour.index()]
+    }
+    pub fn occupied(&self) -> Bitboard {
+        self.occupancy[0] | self.occupancy[1]
+    }
+    pub fn move_piece(&mut self, from : Square, to : Square, promotion : Option<PieceType>) {
+        let moving_pawn = self.piece_at(from).is_some_and(|piece| piece.piece_type.is_pawn());
+        let is_en_passant = self.is_en_passant(from, to);
+        if moving_pawn || is_en_passant || self.piece_at(to).is_some() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if is_en_passant {
+            // the captured pawn sits beside the moving pawn, not on the destination
+            self.set_piece(Square::new(to.file(), from.rank()), None);
+        }
+        // a double pawn push leaves the skipped tile open to an en passant capture for one turn
+        self.en_passant = if moving_pawn && from.rank().abs_diff(to.rank()) == 2 {
+            Some(Square::new(from.file(), (from.rank() + to.rank()) / 2))
+        } else {
+            None
+        };
+        if let Some(piece) = self.piece_at(from) {
+            if piece.piece_type.is_king() {
+                self.castling_rights.remove_all(&piece.colour);
+                if let Some((rook_curr, rook_new)) = castling_rook_tiles(from, to) {
+                    let rook = self.take_piece(rook_curr);
+                    self.set_piece(rook_new, rook);
+                }
+            }
+        }
+        self.castling_rights.remove_for_corner(from);
+        self.castling_rights.remove_for_corner(to);
+        let is_promotion = self.is_promotion(from, to);
+        let mut piece = self.take_piece(from);
+        if is_promotion {
+            // a pawn reaching the last rank must become another piece, queen unless told otherwise
+            if let Some(piece) = piece.as_mut() {
+                piece.piece_type = promotion.unwrap_or(PieceType::Queen);
+            }
+        }
+        self.set_piece(to, piece);
+    }
+    // Plays a move for the side to move and hands the turn over
+    pub fn play_move(&mut self, mv : &Move) {
+        self.move_piece(mv.from, mv.to, mv.promotion);
+        if !self.turn.is_white() {
+            self.fullmove_number += 1;
+        }
+        self.swap_turn();
+        self.in_check = if is_in_check(&self.turn, self) {
+            Some(self.turn)
+        } else {
+            None
+        };
+        self.history.push(self.zobrist_key());
+    }
+    // Plays a move like play_move and returns what unmake_move needs to take it back
+    pub fn make_move(&mut self, mv : Move) -> UndoInfo {
+        let moved = self.piece_at(mv.from).expect("make_move needs a piece on the from tile");
+        let captured_square = if self.is_en_passant(mv.from, mv.to) {
+            Square::new(mv.to.file(), mv.from.rank())
+        } else {
+            mv.to
+        };
+        let undo = UndoInfo {
+            mv,
+            moved,
+            captured: self.piece_at(captured_square).map(|piece| (captured_square, piece)),
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            in_check: self.in_check,
+        };
+        self.play_move(&mv);
+        undo
+    }
+    // Takes back the last move made, leaving the board exactly as it was before make_move
+    pub fn unmake_move(&mut self, undo : UndoInfo) {
+        let UndoInfo { mv, moved, .. } = undo;
+        self.history.pop();
+        self.swap_turn();
+        if !self.turn.is_white() {
+            self.fullmove_number -= 1;
+        }
+        self.set_piece(mv.to, None);
+        self.set_piece(mv.from, Some(moved));
+        if let Some((square, piece)) = undo.captured {
+            self.set_piece(square, Some(piece));
+        }
+        if moved.piece_type.is_king() {
+            if let Some((rook_curr, rook_new)) = castling_rook_tiles(mv.from, mv.to) {
+                let rook = self.take_piece(rook_new);
+                self.set_piece(rook_curr, rook);
+            }
+        }
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.in_check = undo.in_check;
+    }
+    // A 64-bit key identifying the position. Piece placement is kept up to date as pieces move,
+    // the side to move, castling rights and en passant file are cheap enough to add on each call.
+    pub fn zobrist_key(&self) -> u64 {
+        self.placement_key ^ state_key(&self.turn, &self.castling_rights, self.capturable_en_passant())
+    }
+    // The en passant tile only makes positions different if a pawn stands ready to capture onto it
+    pub fn capturable_en_passant(&self) -> Option<Square> {
+        let pawn_rank_delta = if self.turn.is_white() { -1 } else { 1 };
+        self.en_passant.filter(|tile| {
+            [-1, 1].iter().any(|&file_delta| {
+                tile.offset(file_delta, pawn_rank_delta)
+                    .is_some_and(|square| self.piece_at(square) == Some(Piece::new(self.turn, PieceType::Pawn)))
+            })
+        })
+    }
+    pub fn repetition_count(&self) -> usize {
+        let current = self.zobrist_key();
+        self.history.iter().filter(|&&key| key == current).count()
+    }
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= 5
+    }
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+    // K v K, K+minor v K, and any number of bishops that all stand on one tile colour
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut minors = vec![];
+        for (square, piece) in self.pieces() {
+            match piece.piece_type {
+                PieceType::Pawn | PieceType::Rook | PieceType::Queen => return false,
+                PieceType::Bishop | PieceType::Knight => minors.push((piece.piece_type, (square.file() + square.rank()) % 2)),
+                PieceType::King => (),
+            }
+        }
+        minors.len() <= 1
+            || minors
+                .iter()
+                .all(|&(piece_type, tile_colour)| piece_type == PieceType::Bishop && tile_colour == minors[0].1)
+    }
+    pub fn is_promotion(&self, from : Square, to : Square) -> bool {
+        match self.piece_at(from) {
+            Some(piece) if piece.piece_type.is_pawn() => {
+                (piece.colour.is_white() && to.rank() == 7) || (!piece.colour.is_white() && to.rank() == 0)
+            }
+            _ => false,
+        }
+    }
+    pub fn is_en_passant(&self, from : Square, to : Square) -> bool {
+        self.piece_at(from).is_some_and(|piece| piece.piece_type.is_pawn())
+            && from.file() != to.file()
+            && self.en_passant == Some(to)
+    }
+}
+
+impl Default for ArrayBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+
+use crate::board::ArrayBoard;
+use crate::movegen::is_in_check;
+use crate::structs::{Piece, PieceColour, PieceType, Square};
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FenError {
+    WrongFieldCount(usize),
+    WrongRankCount(usize),
+    BadRank { rank: u8, reason: String },
+    InvalidPiece(char),
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+    KingCount { colour: PieceColour, count: usize },
+    PawnOnBackRank(Square),
+    OpponentInCheck,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::WrongFieldCount(count) => {
+                write!(f, "expected 6 space separated fields (or 4 without move counters), found {count}")
+            }
+            FenError::WrongRankCount(count) => write!(f, "piece placement must have 8 ranks separated by '/', found {count}"),
+            FenError::BadRank { rank, reason } => write!(f, "rank {}: {reason}", rank + 1),
+            FenError::InvalidPiece(c) => write!(f, "'{c}' is not a piece letter (one of PNBRQK or pnbrqk)"),
+            FenError::InvalidSideToMove(field) => write!(f, "side to move must be 'w' or 'b', found '{field}'"),
+            FenError::InvalidCastling(field) => {
+                write!(f, "castling rights must be '-' or a combination of KQkq, found '{field}'")
+            }
+            FenError::InvalidEnPassant(field) => {
+                write!(f, "en passant square must be '-' or a square on rank 3 or 6 behind a pawn that just moved two, found '{field}'")
+            }
+            FenError::InvalidHalfmoveClock(field) => {
+                write!(f, "halfmove clock must be a non-negative number, found '{field}'")
+            }
+            FenError::InvalidFullmoveNumber(field) => {
+                write!(f, "fullmove number must be a positive number, found '{field}'")
+            }
+            FenError::KingCount { colour, count } => write!(f, "{colour:?} must have exactly one king, found {count}"),
+            FenError::PawnOnBackRank(square) => write!(f, "pawn on {square} cannot stand on the first or last rank"),
+            FenError::OpponentInCheck => write!(f, "the side that just moved is in check"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl ArrayBoard {
+    // Reads a position in Forsyth-Edwards Notation, e.g. START_FEN
+    pub fn from_fen(fen: &str) -> Result<ArrayBoard, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 6 && fields.len() != 4 {
+            return Err(FenError::WrongFieldCount(fields.len()));
+        }
+        let mut array_board = ArrayBoard::empty();
+
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::WrongRankCount(ranks.len()));
+        }
+        // FEN lists rank 8 first
+        for (i, rank_text) in ranks.iter().enumerate() {
+            let rank = 7 - i as u8;
+            let mut file = 0;
+            let mut after_digit = false;
+            for c in rank_text.chars() {
+                let digit = c.to_digit(10);
+                let width = match digit {
+                    Some(skip) if !(1..=8).contains(&skip) => {
+                        return Err(FenError::BadRank {
+                            rank,
+                            reason: format!("'{rank_text}' skips {skip} squares, a digit must be 1 to 8"),
+                        });
+                    }
+                    // runs of empty squares are written as one digit, "44" is not a way of writing 8
+                    Some(_) if after_digit => {
+                        return Err(FenError::BadRank {
+                            rank,
+                            reason: format!("'{rank_text}' has two digits in a row"),
+                        });
+                    }
+                    Some(skip) => skip as u8,
+                    None => 1,
+                };
+                // checked before counting the square so an overlong rank can neither overflow nor wrap round
+                if file + width > 8 {
+                    return Err(FenError::BadRank {
+                        rank,
+                        reason: format!("'{rank_text}' covers more than 8 squares"),
+                    });
+                }
+                if digit.is_none() {
+                    let piece = piece_from_char(c).ok_or(FenError::InvalidPiece(c))?;
+                    array_board.set_piece(Square::new(file, rank), Some(piece));
+                }
+                after_digit = digit.is_some();
+                file += width;
+            }
+            if file != 8 {
+                return Err(FenError::BadRank {
+                    rank,
+                    reason: format!("'{rank_text}' covers {file} squares instead of 8"),
+                });
+            }
+        }
+
+        array_board.turn = match fields[1] {
+            "w" => PieceColour::White,
+            "b" => PieceColour::Black,
+            other => return Err(FenError::InvalidSideToMove(other.to_string())),
+        };
+
+        if fields[2] != "-" {
+            for c in fields[2].chars() {
+                let right = match c {
+                    'K' => &mut array_board.castling_rights.white_kingside,
+                    'Q' => &mut array_board.castling_rights.white_queenside,
+                    'k' => &mut array_board.castling_rights.black_kingside,
+                    'q' => &mut array_board.castling_rights.black_queenside,
+                    _ => return Err(FenError::InvalidCastling(fields[2].to_string())),
+                };
+                if *right {
+                    return Err(FenError::InvalidCastling(fields[2].to_string()));
+                }
+                *right = true;
+            }
+        }
+
+        if fields[3] != "-" {
+            let square: Square = fields[3]
+                .parse()
+                .map_err(|_| FenError::InvalidEnPassant(fields[3].to_string()))?;
+            // the square behind the pawn that just moved two squares
+            let (ep_rank, pawn_rank_delta) = if array_board.turn.is_white() { (5, -1) } else { (2, 1) };
+            let pawn = Piece::new(array_board.turn.opposite(), PieceType::Pawn);
+            let pawn_square = square.offset(0, pawn_rank_delta);
+            if square.rank() != ep_rank || pawn_square.and_then(|square| array_board.piece_at(square)) != Some(pawn) {
+                return Err(FenError::InvalidEnPassant(fields[3].to_string()));
+            }
+            array_board.en_passant = Some(square);
+        }
+
+        if fields.len() == 6 {
+            array_board.halfmove_clock = fields[4]
+                .parse()
+                .map_err(|_| FenError::InvalidHalfmoveClock(fields[4].to_string()))?;
+            array_board.fullmove_number = fields[5]
+                .parse()
+                .ok()
+                .filter(|&number| number > 0)
+                .ok_or_else(|| FenError::InvalidFullmoveNumber(fields[5].to_string()))?;
+        }
+
+        for colour in [PieceColour::White, PieceColour::Black] {
+            let count = array_board
+                .pieces()
+                .filter(|(_square, piece)| *piece == Piece::new(colour, PieceType::King))
+                .count();
+            if count != 1 {
+                return Err(FenError::KingCount { colour, count });
+            }
+        }
+        if let Some((square, _piece)) = array_board
+            .pieces()
+            .find(|(square, piece)| piece.piece_type.is_pawn() && (square.rank() == 0 || square.rank() == 7))
+        {
+            return Err(FenError::PawnOnBackRank(square));
+        }
+        if is_in_check(&array_board.turn.opposite(), &array_board) {
+            return Err(FenError::OpponentInCheck);
+        }
+
+        array_board.in_check = if is_in_check(&array_board.turn, &array_board) {
+            Some(array_board.turn)
+        } else {
+            None
+        };
+        array_board.history.push(array_board.zobrist_key());
+        Ok(array_board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.piece_at(Square::new(file, rank)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        placement.push(piece_to_char(&piece));
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let side_to_move = if self.turn.is_white() { "w" } else { "b" };
+
+        let rights = self.castling_rights;
+        let mut castling: String = [
+            (rights.white_kingside, 'K'),
+            (rights.white_queenside, 'Q'),
+            (rights.black_kingside, 'k'),
+            (rights.black_queenside, 'q'),
+        ]
+        .iter()
+        .filter(|(allowed, _c)| *allowed)
+        .map(|(_allowed, c)| c)
+        .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = self
+            .en_passant
+            .map_or("-".to_string(), |square| square.to_string());
+
+        format!(
+            "{placement} {side_to_move} {castling} {en_passant} {} {}",
+            self.halfmove_clock, self.fullmove_number
+        )
+    }
+}
+
+fn piece_from_char(c: char) -> Option<Piece> {
+    let colour = if c.is_ascii_uppercase() { PieceColour::White } else { PieceColour::Black };
+    PieceType::from_char(c.to_ascii_uppercase()).map(|piece_type| Piece::new(colour, piece_type))
+}
+
+fn piece_to_char(piece: &Piece) -> char {
+    let c = piece.piece_type.to_char();
+    if piece.colour.is_white() {
+        c
+    } else {
+        c.to_ascii_lowercase()
+    }
+}
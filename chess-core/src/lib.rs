@@ -0,0 +1,21 @@
+#![deny(clippy::all)]
+
+// The rules of chess with no game engine attached: the board, move generation and game outcomes.
+
+pub mod bitboard;
+pub mod board;
+pub mod fen;
+pub mod movegen;
+pub mod notation;
+pub mod perft;
+pub mod pgn;
+pub mod structs;
+pub mod zobrist;
+
+pub use bitboard::Bitboard;
+pub use board::ArrayBoard;
+pub use fen::{FenError, START_FEN};
+pub use movegen::{game_outcome, legal_moves, legal_moves_mut};
+pub use notation::MoveParseError;
+pub use pgn::{parse_pgn, PgnError, PgnGame};
+pub use structs::{CastlingRights, DrawReason, GameOutcome, Move, Piece, PieceColour, PieceType, Square, UndoInfo};
@@ -0,0 +1,206 @@
+use crate::bitboard::{bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks, squares, Bitboard};
+use crate::board::ArrayBoard;
+use crate::structs::{DrawReason, GameOutcome, Move, Piece, PieceColour, PieceType, Square};
+
+const PROMOTION_TYPES: [PieceType; 4] = [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight];
+
+// Every move the side to move can legally play
+pub fn legal_moves(array_board: &ArrayBoard) -> Vec<Move> {
+    legal_moves_mut(&mut array_board.clone())
+}
+
+// The same as legal_moves without copying the board, which is tried each move on and is left as it was.
+// This is the only place that decides legality.
+pub fn legal_moves_mut(array_board: &mut ArrayBoard) -> Vec<Move> {
+    let colour = array_board.turn;
+    pseudo_legal_moves(array_board)
+        .into_iter()
+        .filter(|mv| {
+            // a move is legal if it does not leave its own king attacked
+            let undo = array_board.make_move(*mv);
+            let legal = !is_in_check(&colour, array_board);
+            array_board.unmake_move(undo);
+            legal
+        })
+        .collect()
+}
+
+// Moves that follow each piece's movement rules but may leave the king in check
+fn pseudo_legal_moves(array_board: &ArrayBoard) -> Vec<Move> {
+    let mut to_return = vec![];
+    let colour = array_board.turn;
+    let occupied = array_board.occupied();
+    for (from, piece) in array_board.pieces().filter(|(_square, piece)| piece.colour == colour) {
+        let attacks = match piece.piece_type {
+            PieceType::King => {
+                to_return.extend(castling_moves(from, &piece, array_board));
+                king_attacks(from)
+            }
+            PieceType::Queen => queen_attacks(from, occupied),
+            PieceType::Bishop => bishop_attacks(from, occupied),
+            PieceType::Rook => rook_attacks(from, occupied),
+            PieceType::Knight => knight_attacks(from),
+            PieceType::Pawn => {
+                to_return.extend(pawn_moves(from, &piece, array_board));
+                continue;
+            }
+        };
+        // any attacked tile that is empty or holds an enemy piece
+        let targets = attacks & !array_board.colour_occupancy(&colour);
+        to_return.extend(squares(targets).map(|to| Move::new(from, to, piece, array_board.piece_at(to))));
+    }
+    to_return
+}
+
+fn pawn_moves(from: Square, piece: &Piece, array_board: &ArrayBoard) -> Vec<Move> {
+    let mut to_return: Vec<Move> = vec![];
+    let (direction, start_rank, last_rank) = if piece.colour.is_white() { (1, 1, 7) } else { (-1, 6, 0) };
+    let Some(ahead) = from.offset(0, direction) else {
+        return to_return;
+    };
+
+    // pushes only onto empty tiles, the double push only from the start rank and through an empty tile
+    if array_board.piece_at(ahead).is_none() {
+        to_return.push(Move::new(from, ahead, *piece, None));
+        if let Some(double) = from.offset(0, 2 * direction).filter(|_| from.rank() == start_rank) {
+            if array_board.piece_at(double).is_none() {
+                to_return.push(Move::new(from, double, *piece, None));
+            }
+        }
+    }
+
+    // captures only diagonally, onto an enemy piece or the en passant tile
+    for file_delta in [-1, 1] {
+        let Some(to) = from.offset(file_delta, direction) else { continue; };
+        match array_board.piece_at(to) {
+            Some(target) if target.colour.is_different(&piece.colour) => {
+                to_return.push(Move::new(from, to, *piece, Some(target)));
+            }
+            None if array_board.is_en_passant(from, to) => {
+                let captured = Piece::new(piece.colour.opposite(), PieceType::Pawn);
+                to_return.push(Move {
+                    is_en_passant: true,
+                    ..Move::new(from, to, *piece, Some(captured))
+                });
+            }
+            _ => (),
+        }
+    }
+
+    // reaching the last rank turns every move into one move per promotion piece
+    if ahead.rank() == last_rank {
+        to_return = to_return
+            .into_iter()
+            .flat_map(|mv| {
+                PROMOTION_TYPES.map(|piece_type| Move {
+                    promotion: Some(piece_type),
+                    ..mv
+                })
+            })
+            .collect();
+    }
+    to_return
+}
+
+// Returns the rook's (current, new) tile if moving the king between these tiles castles
+pub fn castling_rook_tiles(from : Square, to : Square) -> Option<(Square, Square)> {
+    if from.rank() != to.rank() || from.file().abs_diff(to.file()) != 2 {
+        return None;
+    }
+    if to.file() > from.file() {
+        Some((Square::new(7, from.rank()), Square::new(5, from.rank())))
+    } else {
+        Some((Square::new(0, from.rank()), Square::new(3, from.rank())))
+    }
+}
+
+fn castling_moves(from: Square, king: &Piece, array_board: &ArrayBoard) -> Vec<Move> {
+    let mut to_return = vec![];
+    let colour = &king.colour;
+    let home_rank = if colour.is_white() { 0 } else { 7 };
+    if from != Square::new(4, home_rank) {
+        return to_return;
+    }
+    let is_empty = |file: u8| array_board.piece_at(Square::new(file, home_rank)).is_none();
+    let is_rook = |file: u8| {
+        array_board.piece_at(Square::new(file, home_rank))
+            .is_some_and(|piece| piece.piece_type == PieceType::Rook && piece.colour == *colour)
+    };
+    // the king may not castle out of, through or into check
+    let is_safe = |file: u8| !is_tile_attacked(Square::new(file, home_rank), &colour.opposite(), array_board);
+    let castle_to = |file: u8| Move {
+        is_castling: true,
+        ..Move::new(from, Square::new(file, home_rank), *king, None)
+    };
+
+    if array_board.castling_rights.kingside(colour)
+        && is_rook(7)
+        && is_empty(5)
+        && is_empty(6)
+        && is_safe(4)
+        && is_safe(5)
+        && is_safe(6)
+    {
+        to_return.push(castle_to(6));
+    }
+    if array_board.castling_rights.queenside(colour)
+        && is_rook(0)
+        && is_empty(1)
+        && is_empty(2)
+        && is_empty(3)
+        && is_safe(4)
+        && is_safe(3)
+        && is_safe(2)
+    {
+        to_return.push(castle_to(2));
+    }
+    to_return
+}
+
+pub fn is_tile_attacked(tile : Square, attacker: &PieceColour, array_board: &ArrayBoard) -> bool {
+    let occupied = array_board.occupied();
+    let attackers = |piece_types: &[PieceType]| -> Bitboard {
+        piece_types
+            .iter()
+            .fold(0, |pieces, &piece_type| pieces | array_board.pieces_of(attacker, piece_type))
+    };
+    // every attack is symmetric, so look outwards from the tile as each kind of piece and see what it hits.
+    // Pawns are the exception, a white pawn attacks the tile if a black pawn on the tile would attack it.
+    pawn_attacks(&attacker.opposite(), tile) & attackers(&[PieceType::Pawn]) != 0
+        || knight_attacks(tile) & attackers(&[PieceType::Knight]) != 0
+        || king_attacks(tile) & attackers(&[PieceType::King]) != 0
+        || bishop_attacks(tile, occupied) & attackers(&[PieceType::Bishop, PieceType::Queen]) != 0
+        || rook_attacks(tile, occupied) & attackers(&[PieceType::Rook, PieceType::Queen]) != 0
+}
+
+pub fn fetch_king_tile(colour: &PieceColour, array_board: &ArrayBoard) -> Option<Square> {
+    squares(array_board.pieces_of(colour, PieceType::King)).next()
+}
+
+pub fn is_in_check(colour: &PieceColour, array_board: &ArrayBoard) -> bool {
+    fetch_king_tile(colour, array_board).is_some_and(|king_tile| is_tile_attacked(king_tile, &colour.opposite(), array_board))
+}
+
+// The game is over once the side to move has no legal move (mate if its king is attacked, otherwise stalemate)
+// or one of the draw rules applies
+pub fn game_outcome(array_board: &ArrayBoard) -> GameOutcome {
+    let colour = array_board.turn;
+    if !legal_moves(array_board).is_empty() {
+        return if array_board.is_insufficient_material() {
+            GameOutcome::Draw(DrawReason::InsufficientMaterial)
+        } else if array_board.is_fivefold_repetition() {
+            GameOutcome::Draw(DrawReason::FivefoldRepetition)
+        } else if array_board.is_threefold_repetition() {
+            GameOutcome::Draw(DrawReason::ThreefoldRepetition)
+        } else if array_board.is_fifty_move_draw() {
+            GameOutcome::Draw(DrawReason::FiftyMoveRule)
+        } else {
+            GameOutcome::InProgress
+        };
+    }
+    if is_in_check(&colour, array_board) {
+        GameOutcome::Checkmate { winner: colour.opposite() }
+    } else {
+        GameOutcome::Stalemate
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use crate::board::ArrayBoard;
+use crate::movegen::{is_in_check, legal_moves};
+use crate::structs::{Move, PieceType, Square};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MoveParseError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveParseError::InvalidSyntax(text) => write!(f, "'{text}' is not written like a move"),
+            MoveParseError::IllegalMove(text) => write!(f, "'{text}' is not a legal move in this position"),
+            MoveParseError::AmbiguousMove(text) => {
+                write!(f, "'{text}' could be more than one legal move, say which piece moves")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveParseError {}
+
+impl Move {
+    // Standard Algebraic Notation for this move played from `array_board`, e.g. "Nbd2", "exd5", "e8=Q+" or "O-O"
+    pub fn to_san(&self, array_board: &ArrayBoard) -> String {
+        let mut san = if self.is_castling {
+            if self.to.file() > self.from.file() { "O-O" } else { "O-O-O" }.to_string()
+        } else {
+            let mut san = String::new();
+            if self.piece.piece_type.is_pawn() {
+                if self.captured.is_some() {
+                    san.push(file_char(self.from));
+                }
+            } else {
+                san.push(self.piece.piece_type.to_char());
+                san.push_str(&self.disambiguation(array_board));
+            }
+            if self.captured.is_some() {
+                san.push('x');
+            }
+            san.push_str(&self.to.to_string());
+            if let Some(piece_type) = self.promotion {
+                san.push('=');
+                san.push(piece_type.to_char());
+            }
+            san
+        };
+
+        let mut after = array_board.clone();
+        after.make_move(*self);
+        if is_in_check(&after.turn, &after) {
+            san.push(if legal_moves(&after).is_empty() { '#' } else { '+' });
+        }
+        san
+    }
+    // Long algebraic notation as used by UCI, e.g. "e2e4" or "e7e8q"
+    pub fn to_uci(&self) -> String {
+        self.to_string()
+    }
+    // Just enough of the from tile to tell this move apart from other pieces of the same type reaching the same tile
+    fn disambiguation(&self, array_board: &ArrayBoard) -> String {
+        let rivals: Vec<Square> = legal_moves(array_board)
+            .into_iter()
+            .filter(|other| other.to == self.to && other.from != self.from && other.piece == self.piece)
+            .map(|other| other.from)
+            .collect();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|rival| rival.file() != self.from.file()) {
+            file_char(self.from).to_string()
+        } else if rivals.iter().all(|rival| rival.rank() != self.from.rank()) {
+            rank_char(self.from).to_string()
+        } else {
+            self.from.to_string()
+        }
+    }
+}
+
+impl ArrayBoard {
+    // Finds the legal move written in Standard Algebraic Notation. Check and annotation marks
+    // (+, #, !, ?) are ignored, and castling may be written with zeros as well as letters.
+    pub fn parse_san(&self, san: &str) -> Result<Move, MoveParseError> {
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let invalid = || MoveParseError::InvalidSyntax(san.to_string());
+
+        let castling_file = match text {
+            "O-O" | "0-0" => Some(6),
+            "O-O-O" | "0-0-0" => Some(2),
+            _ => None,
+        };
+        if let Some(file) = castling_file {
+            return self.find_move(san, |mv| mv.is_castling && mv.to.file() == file);
+        }
+
+        let mut chars: Vec<char> = text.chars().collect();
+        let piece_type = match chars.first().copied().and_then(PieceType::from_char) {
+            Some(piece_type) => {
+                chars.remove(0);
+                piece_type
+            }
+            None => PieceType::Pawn,
+        };
+        // the promotion piece comes last, with or without an '='
+        let promotion = match chars.last().copied().and_then(PieceType::from_char) {
+            Some(promotion) => {
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+                Some(promotion)
+            }
+            None => None,
+        };
+        if chars.len() < 2 {
+            return Err(invalid());
+        }
+        let to: Square = chars.split_off(chars.len() - 2).iter().collect::<String>().parse().map_err(|_| invalid())?;
+        if chars.last() == Some(&'x') {
+            chars.pop();
+        }
+        // whatever is left names the from tile's file, rank or both
+        let mut from_file = None;
+        let mut from_rank = None;
+        for c in chars {
+            match c {
+                'a'..='h' if from_file.is_none() => from_file = Some(c as u8 - b'a'),
+                '1'..='8' if from_rank.is_none() => from_rank = Some(c as u8 - b'1'),
+                _ => return Err(invalid()),
+            }
+        }
+
+        self.find_move(san, |mv| {
+            mv.piece.piece_type == piece_type
+                && mv.to == to
+                && mv.promotion == promotion
+                && from_file.is_none_or(|file| mv.from.file() == file)
+                && from_rank.is_none_or(|rank| mv.from.rank() == rank)
+        })
+    }
+    // Finds the legal move written in UCI long algebraic notation, e.g. "e2e4", "e1g1" or "e7e8q"
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, MoveParseError> {
+        let text = uci.trim();
+        let invalid = || MoveParseError::InvalidSyntax(uci.to_string());
+        if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+            return Err(invalid());
+        }
+        let from: Square = text[0..2].parse().map_err(|_| invalid())?;
+        let to: Square = text[2..4].parse().map_err(|_| invalid())?;
+        let promotion = match text[4..].chars().next() {
+            Some(c) => Some(
+                PieceType::from_char(c.to_ascii_uppercase())
+                    .filter(|piece_type| !piece_type.is_pawn() && !piece_type.is_king())
+                    .ok_or_else(invalid)?,
+            ),
+            None => None,
+        };
+        self.find_move(uci, |mv| mv.from == from && mv.to == to && mv.promotion == promotion)
+    }
+    fn find_move(&self, text: &str, matches: impl Fn(&Move) -> bool) -> Result<Move, MoveParseError> {
+        let mut found = legal_moves(self).into_iter().filter(|mv| matches(mv));
+        match (found.next(), found.next()) {
+            (Some(mv), None) => Ok(mv),
+            (None, _) => Err(MoveParseError::IllegalMove(text.to_string())),
+            (Some(_), Some(_)) => Err(MoveParseError::AmbiguousMove(text.to_string())),
+        }
+    }
+}
+
+fn file_char(square: Square) -> char {
+    (b'a' + square.file()) as char
+}
+
+fn rank_char(square: Square) -> char {
+    (b'1' + square.rank()) as char
+}
@@ -0,0 +1,44 @@
+use crate::board::ArrayBoard;
+use crate::movegen::legal_moves_mut;
+use crate::structs::Move;
+
+// Counts the leaf nodes of the legal move tree `depth` plies deep
+pub fn perft(array_board: &ArrayBoard, depth: u32) -> u64 {
+    perft_mut(&mut array_board.clone(), depth)
+}
+
+// The perft count below each root move, for finding which move a generator gets wrong
+pub fn perft_divide(array_board: &ArrayBoard, depth: u32) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return vec![];
+    }
+    let mut array_board = array_board.clone();
+    legal_moves_mut(&mut array_board)
+        .into_iter()
+        .map(|mv| {
+            let undo = array_board.make_move(mv);
+            let nodes = perft_mut(&mut array_board, depth - 1);
+            array_board.unmake_move(undo);
+            (mv, nodes)
+        })
+        .collect()
+}
+
+fn perft_mut(array_board: &mut ArrayBoard, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = legal_moves_mut(array_board);
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .into_iter()
+        .map(|mv| {
+            let undo = array_board.make_move(mv);
+            let nodes = perft_mut(array_board, depth - 1);
+            array_board.unmake_move(undo);
+            nodes
+        })
+        .sum()
+}
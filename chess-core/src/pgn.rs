@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::board::ArrayBoard;
+use crate::fen::{FenError, START_FEN};
+use crate::notation::MoveParseError;
+use crate::structs::{GameOutcome, Move, PieceColour};
+
+// The tags every PGN game carries, in the order they must be written
+pub const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+// Movetext lines are kept under the 80 columns the PGN standard asks for
+const LINE_LENGTH: usize = 79;
+
+// A game as PGN sees it: tags, the position it started from and the moves played
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub start: ArrayBoard,
+    pub moves: Vec<Move>,
+}
+
+impl PgnGame {
+    // A game with no moves yet and unknown ("?") values in the Seven Tag Roster.
+    // A start other than the usual one is recorded in SetUp and FEN tags.
+    pub fn new(start: ArrayBoard) -> Self {
+        let mut tags: Vec<(String, String)> = SEVEN_TAG_ROSTER
+            .iter()
+            .map(|&name| (name.to_string(), "?".to_string()))
+            .collect();
+        let fen = start.to_fen();
+        if fen != START_FEN {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), fen));
+        }
+        let mut game = PgnGame {
+            tags,
+            start,
+            moves: vec![],
+        };
+        game.set_tag("Date", "????.??.??");
+        game.set_tag("Result", "*");
+        game
+    }
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _value)| tag_name == name)
+            .map(|(_tag_name, value)| value.as_str())
+    }
+    // Replaces the tag's value, or adds the tag at the end if the game does not have it yet
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _value)| tag_name == name) {
+            Some((_tag_name, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+    // The position after every move has been played
+    pub fn final_position(&self) -> ArrayBoard {
+        let mut array_board = self.start.clone();
+        for mv in &self.moves {
+            array_board.make_move(*mv);
+        }
+        array_board
+    }
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+        }
+        pgn.push('\n');
+
+        let mut tokens = vec![];
+        let mut array_board = self.start.clone();
+        for (i, mv) in self.moves.iter().enumerate() {
+            if array_board.turn == PieceColour::White {
+                tokens.push(format!("{}.", array_board.fullmove_number));
+            } else if i == 0 {
+                tokens.push(format!("{}...", array_board.fullmove_number));
+            }
+            tokens.push(mv.to_san(&array_board));
+            array_board.make_move(*mv);
+        }
+        tokens.push(self.tag("Result").unwrap_or("*").to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > LINE_LENGTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+        pgn
+    }
+}
+
+// The Result tag for an outcome, "*" while the game is still going
+pub fn result_tag(outcome: &GameOutcome) -> &'static str {
+    match outcome {
+        GameOutcome::InProgress => "*",
+        GameOutcome::Checkmate { winner: PieceColour::White } => "1-0",
+        GameOutcome::Checkmate { winner: PieceColour::Black } => "0-1",
+        GameOutcome::Stalemate | GameOutcome::Draw(_) => "1/2-1/2",
+    }
+}
+
+// Where in the PGN text a problem was found (both counted from 1) and what it was
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PgnError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PgnErrorKind,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PgnErrorKind {
+    UnexpectedCharacter(char),
+    BadTag(String),
+    UnterminatedString,
+    UnterminatedComment,
+    UnmatchedParenthesis,
+    UnclosedVariation,
+    InvalidFen(FenError),
+    InvalidMove(MoveParseError),
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            PgnErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{c}'"),
+            PgnErrorKind::BadTag(reason) => write!(f, "bad tag pair, {reason}"),
+            PgnErrorKind::UnterminatedString => write!(f, "string is missing its closing '\"'"),
+            PgnErrorKind::UnterminatedComment => write!(f, "comment is missing its closing '}}'"),
+            PgnErrorKind::UnmatchedParenthesis => write!(f, "')' with no variation to close"),
+            PgnErrorKind::UnclosedVariation => write!(f, "variation is missing its closing ')'"),
+            PgnErrorKind::InvalidFen(error) => write!(f, "invalid FEN tag, {error}"),
+            PgnErrorKind::InvalidMove(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+// Reads every game in a PGN file. Comments, NAGs ($1), annotation marks (!?), escape lines (%)
+// and variations are skipped, only the main line's moves are kept.
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut reader = PgnReader {
+        chars: text.chars().collect(),
+        position: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut games = vec![];
+    let mut tags: Vec<(String, String)> = vec![];
+    // the game being read and the position its moves have reached
+    let mut current: Option<(PgnGame, ArrayBoard)> = None;
+    let mut variation_depth = 0;
+    let mut variation_start = (0, 0);
+
+    while let Some(c) = reader.peek() {
+        let (line, column) = (reader.line, reader.column);
+        let error = |kind| PgnError { line, column, kind };
+        match c {
+            _ if c.is_whitespace() => {
+                reader.next();
+            }
+            '%' if column == 1 => reader.skip_line(),
+            ';' => reader.skip_line(),
+            '{' => {
+                while reader.next().ok_or(error(PgnErrorKind::UnterminatedComment))? != '}' {}
+            }
+            '(' => {
+                reader.next();
+                if variation_depth == 0 {
+                    variation_start = (line, column);
+                }
+                variation_depth += 1;
+            }
+            ')' => {
+                reader.next();
+                if variation_depth == 0 {
+                    return Err(error(PgnErrorKind::UnmatchedParenthesis));
+                }
+                variation_depth -= 1;
+            }
+            '[' if variation_depth == 0 => {
+                // a tag section after some moves starts the next game
+                if let Some((game, _array_board)) = current.take() {
+                    games.push(game);
+                }
+                tags.push(reader.tag_pair().map_err(error)?);
+            }
+            '$' | '.' | '!' | '?' => {
+                reader.next();
+                while reader.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    reader.next();
+                }
+            }
+            _ if c.is_ascii_alphanumeric() || c == '*' => {
+                let symbol = reader.symbol();
+                if variation_depth > 0 || symbol.chars().all(|c| c.is_ascii_digit()) {
+                    // moves inside variations are not checked and move numbers carry no information
+                    continue;
+                }
+                if current.is_none() {
+                    current = Some(start_game(std::mem::take(&mut tags)).map_err(error)?);
+                }
+                let Some((game, array_board)) = current.as_mut() else { continue; };
+                if RESULTS.contains(&symbol.as_str()) {
+                    game.set_tag("Result", &symbol);
+                    games.extend(current.take().map(|(game, _array_board)| game));
+                    continue;
+                }
+                let mv = array_board
+                    .parse_san(&symbol)
+                    .map_err(|parse_error| error(PgnErrorKind::InvalidMove(parse_error)))?;
+                array_board.make_move(mv);
+                game.moves.push(mv);
+            }
+            _ => return Err(error(PgnErrorKind::UnexpectedCharacter(c))),
+        }
+    }
+
+    if variation_depth > 0 {
+        let (line, column) = variation_start;
+        return Err(PgnError { line, column, kind: PgnErrorKind::UnclosedVariation });
+    }
+    match current {
+        Some((game, _array_board)) => games.push(game),
+        // a tag section with no movetext at all is still a game, just an empty one
+        None if !tags.is_empty() => games.push(start_game(tags).map_err(|kind| PgnError {
+            line: reader.line,
+            column: reader.column,
+            kind,
+        })?.0),
+        None => (),
+    }
+    Ok(games)
+}
+
+// A game starting from the FEN tag if there is one, carrying the tags read for it
+fn start_game(tags: Vec<(String, String)>) -> Result<(PgnGame, ArrayBoard), PgnErrorKind> {
+    let start = match tags.iter().find(|(name, _value)| name == "FEN") {
+        Some((_name, fen)) => ArrayBoard::from_fen(fen).map_err(PgnErrorKind::InvalidFen)?,
+        None => ArrayBoard::new(),
+    };
+    let mut game = PgnGame::new(start.clone());
+    for (name, value) in &tags {
+        game.set_tag(name, value);
+    }
+    Ok((game, start))
+}
+
+// Walks the text one character at a time, keeping track of the line and column for errors
+struct PgnReader {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl PgnReader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+    fn skip_line(&mut self) {
+        while self.next().is_some_and(|c| c != '\n') {}
+    }
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+    // A move, move number or result: letters, digits and the marks that can appear inside a move
+    fn symbol(&mut self) -> String {
+        let mut symbol = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || "_+#=:-/*".contains(*c)) {
+            symbol.push(c);
+            self.next();
+        }
+        symbol
+    }
+    // [Name "value"], with \" and \\ escapes inside the value
+    fn tag_pair(&mut self) -> Result<(String, String), PgnErrorKind> {
+        self.next();
+        self.skip_spaces();
+        let name = self.symbol();
+        if name.is_empty() {
+            return Err(PgnErrorKind::BadTag("expected a tag name after '['".to_string()));
+        }
+        self.skip_spaces();
+        if self.next() != Some('"') {
+            return Err(PgnErrorKind::BadTag(format!("expected a quoted value after {name}")));
+        }
+        let mut value = String::new();
+        loop {
+            match self.next().ok_or(PgnErrorKind::UnterminatedString)? {
+                '"' => break,
+                '\\' => value.push(self.next().ok_or(PgnErrorKind::UnterminatedString)?),
+                '\n' => return Err(PgnErrorKind::UnterminatedString),
+                c => value.push(c),
+            }
+        }
+        self.skip_spaces();
+        if self.next() != Some(']') {
+            return Err(PgnErrorKind::BadTag(format!("expected ']' to close the {name} tag")));
+        }
+        Ok((name, value))
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::str::FromStr;
+
+// A tile of the board, numbered 0..64 from a1 along each rank up to h8
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Square(u8);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseSquareError(pub String);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PieceType {
+    Pawn,
+    King,
+    Queen,
+    Rook,
+    Bishop,
+    Knight,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PieceColour {
+    White,
+    Black,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameOutcome {
+    InProgress,
+    Checkmate { winner: PieceColour },
+    Stalemate,
+    Draw(DrawReason),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DrawReason {
+    FiftyMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    InsufficientMaterial,
+}
+
+// A move together with everything needed to play it on the board and on screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub piece: Piece,
+    pub captured: Option<Piece>,
+    pub promotion: Option<PieceType>,
+    pub is_castling: bool,
+    pub is_en_passant: bool,
+}
+
+// Everything make_move changes that the move alone cannot restore, handed back to unmake_move
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UndoInfo {
+    pub mv: Move,
+    // the piece that left the from tile, a pawn even if it promoted
+    pub moved: Piece,
+    // where the captured piece stood, which is not the destination for en passant
+    pub captured: Option<(Square, Piece)>,
+    pub castling_rights: CastlingRights,
+    pub en_passant: Option<Square>,
+    pub halfmove_clock: u32,
+    pub in_check: Option<PieceColour>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Piece {
+    pub colour: PieceColour,
+    pub piece_type: PieceType,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+impl Piece {
+    pub fn new(colour: PieceColour, piece_type: PieceType) -> Self {
+        Piece {
+            colour,
+            piece_type,
+        }
+    }
+}
+
+impl Move {
+    pub fn new(from: Square, to: Square, piece: Piece, captured: Option<Piece>) -> Self {
+        Move {
+            from,
+            to,
+            piece,
+            captured,
+            promotion: None,
+            is_castling: false,
+            is_en_passant: false,
+        }
+    }
+}
+
+// Long algebraic notation as used by UCI, e.g. "e2e4" or "e7e8q"
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        if let Some(piece_type) = self.promotion {
+            write!(f, "{}", piece_type.to_char().to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+impl Square {
+    pub fn new(file: u8, rank: u8) -> Self {
+        assert!(file < 8 && rank < 8, "square off the board: file {file}, rank {rank}");
+        Square(rank * 8 + file)
+    }
+    pub fn from_index(index: usize) -> Self {
+        assert!(index < 64, "square index off the board: {index}");
+        Square(index as u8)
+    }
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+    pub fn file(&self) -> u8 {
+        self.0 % 8
+    }
+    pub fn rank(&self) -> u8 {
+        self.0 / 8
+    }
+    // The square reached by stepping (file_delta, rank_delta), if it is still on the board
+    pub fn offset(&self, file_delta: i8, rank_delta: i8) -> Option<Square> {
+        let file = self.file() as i8 + file_delta;
+        let rank = self.rank() as i8 + rank_delta;
+        if (0..8).contains(&file) && (0..8).contains(&rank) {
+            Some(Square::new(file as u8, rank as u8))
+        } else {
+            None
+        }
+    }
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file()) as char, self.rank() + 1)
+    }
+}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    // Parses algebraic names such as "e4"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.as_bytes() {
+            &[file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Ok(Square::new(file - b'a', rank - b'1')),
+            _ => Err(ParseSquareError(format!("'{name}' is not a square name like \"e4\""))),
+        }
+    }
+}
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSquareError {}
+
+impl PieceColour {
+    pub fn is_white(&self) -> bool {
+        matches!(self, PieceColour::White)
+    }
+    pub fn is_different(&self, other: &PieceColour) -> bool {
+        matches!(
+            (self, other),
+            (PieceColour::White, PieceColour::Black) | (PieceColour::Black, PieceColour::White)
+        )
+    }
+    // White is 0 and Black is 1, for tables kept per colour
+    pub fn index(&self) -> usize {
+        match self {
+            PieceColour::White => 0,
+            PieceColour::Black => 1,
+        }
+    }
+    pub fn opposite(&self) -> PieceColour {
+        match self {
+            PieceColour::White => PieceColour::Black,
+            PieceColour::Black => PieceColour::White,
+        }
+    }
+}
+
+impl PieceType {
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::King,
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ];
+
+    // The position in PieceType::ALL, for tables kept per piece type
+    pub fn index(&self) -> usize {
+        match self {
+            PieceType::Pawn => 0,
+            PieceType::King => 1,
+            PieceType::Queen => 2,
+            PieceType::Rook => 3,
+            PieceType::Bishop => 4,
+            PieceType::Knight => 5,
+        }
+    }
+    // The upper case letter used by FEN and SAN, e.g. 'N' for a knight
+    pub fn to_char(&self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::King => 'K',
+            PieceType::Queen => 'Q',
+            PieceType::Rook => 'R',
+            PieceType::Bishop => 'B',
+            PieceType::Knight => 'N',
+        }
+    }
+    pub fn from_char(c: char) -> Option<PieceType> {
+        PieceType::ALL.into_iter().find(|piece_type| piece_type.to_char() == c)
+    }
+    pub fn is_king(&self) -> bool {
+        matches!(self, PieceType::King)
+    }
+    pub fn is_pawn(&self) -> bool {
+        matches!(self, PieceType::Pawn)
+    }
+}
+
+impl CastlingRights {
+    pub fn new() -> Self {
+        CastlingRights {
+            white_kingside: true,
+            white_queenside: true,
+            black_kingside: true,
+            black_queenside: true,
+        }
+    }
+    pub fn none() -> Self {
+        CastlingRights {
+            white_kingside: false,
+            white_queenside: false,
+            black_kingside: false,
+            black_queenside: false,
+        }
+    }
+    pub fn kingside(&self, colour: &PieceColour) -> bool {
+        match colour {
+            PieceColour::White => self.white_kingside,
+            PieceColour::Black => self.black_kingside,
+        }
+    }
+    pub fn queenside(&self, colour: &PieceColour) -> bool {
+        match colour {
+            PieceColour::White => self.white_queenside,
+            PieceColour::Black => self.black_queenside,
+        }
+    }
+    pub fn remove_all(&mut self, colour: &PieceColour) {
+        match colour {
+            PieceColour::White => {
+                self.white_kingside = false;
+                self.white_queenside = false;
+            }
+            PieceColour::Black => {
+                self.black_kingside = false;
+                self.black_queenside = false;
+            }
+        }
+    }
+    // a rook leaving or being captured on its starting corner loses that side's right
+    pub fn remove_for_corner(&mut self, square: Square) {
+        match (square.file(), square.rank()) {
+            (0, 0) => self.white_queenside = false,
+            (7, 0) => self.white_kingside = false,
+            (0, 7) => self.black_queenside = false,
+            (7, 7) => self.black_kingside = false,
+            _ => (),
+        }
+    }
+}
+
+impl Default for CastlingRights {
+    fn default() -> Self {
+        Self::new()
+    }
+}
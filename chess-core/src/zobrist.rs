@@ -0,0 +1,72 @@
+use crate::board::ArrayBoard;
+use crate::structs::{CastlingRights, Piece, PieceColour, Square};
+
+// Fixed pseudo-random numbers, so keys are the same on every run and can be stored
+const PIECE_KEYS: [[u64; 64]; 12] = piece_keys();
+const BLACK_TO_MOVE_KEY: u64 = random(768);
+const CASTLING_KEYS: [u64; 4] = [random(769), random(770), random(771), random(772)];
+const EN_PASSANT_KEYS: [u64; 8] = [
+    random(773),
+    random(774),
+    random(775),
+    random(776),
+    random(777),
+    random(778),
+    random(779),
+    random(780),
+];
+
+// The n-th output of the SplitMix64 generator
+const fn random(n: u64) -> u64 {
+    let mut z = 0x5EED_C0FF_EE15_600D_u64.wrapping_add((n + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn piece_keys() -> [[u64; 64]; 12] {
+    let mut keys = [[0; 64]; 12];
+    let mut piece = 0;
+    while piece < 12 {
+        let mut square = 0;
+        while square < 64 {
+            keys[piece][square] = random((piece * 64 + square) as u64);
+            square += 1;
+        }
+        piece += 1;
+    }
+    keys
+}
+
+// The key of one piece standing on one square, xored in and out as pieces move
+pub fn piece_key(piece: &Piece, square: Square) -> u64 {
+    PIECE_KEYS[piece.colour.index() * 6 + piece.piece_type.index()][square.index()]
+}
+
+// The part of the key that is not piece placement: side to move, castling rights and en passant file
+pub fn state_key(turn: &PieceColour, castling_rights: &CastlingRights, en_passant: Option<Square>) -> u64 {
+    let mut key = if turn.is_white() { 0 } else { BLACK_TO_MOVE_KEY };
+    let rights = [
+        castling_rights.white_kingside,
+        castling_rights.white_queenside,
+        castling_rights.black_kingside,
+        castling_rights.black_queenside,
+    ];
+    for (allowed, castling_key) in rights.iter().zip(CASTLING_KEYS) {
+        if *allowed {
+            key ^= castling_key;
+        }
+    }
+    if let Some(square) = en_passant {
+        key ^= EN_PASSANT_KEYS[square.file() as usize];
+    }
+    key
+}
+
+// The key built up from nothing, which ArrayBoard::zobrist_key must always agree with
+pub fn zobrist_from_scratch(array_board: &ArrayBoard) -> u64 {
+    array_board
+        .pieces()
+        .fold(0, |key, (square, piece)| key ^ piece_key(&piece, square))
+        ^ state_key(&array_board.turn, &array_board.castling_rights, array_board.capturable_en_passant())
+}
@@ -0,0 +1,107 @@
+use chess_core::{game_outcome, legal_moves, ArrayBoard, CastlingRights, DrawReason, GameOutcome, Piece, PieceColour, PieceType, Square, UndoInfo};
+use PieceColour::{Black, White};
+use PieceType::{Bishop, King, Knight, Pawn, Rook};
+
+// A board holding only the given pieces, with no castling rights
+fn position(pieces: &[(&str, PieceColour, PieceType)], turn: PieceColour, halfmove_clock: u32) -> ArrayBoard {
+    let mut array_board = ArrayBoard::new();
+    for square in Square::all() {
+        array_board.set_piece(square, None);
+    }
+    for &(name, colour, piece_type) in pieces {
+        array_board.set_piece(name.parse().unwrap(), Some(Piece::new(colour, piece_type)));
+    }
+    array_board.turn = turn;
+    array_board.castling_rights = CastlingRights {
+        white_kingside: false,
+        white_queenside: false,
+        black_kingside: false,
+        black_queenside: false,
+    };
+    array_board.halfmove_clock = halfmove_clock;
+    array_board.history = vec![array_board.zobrist_key()];
+    array_board
+}
+
+fn play(array_board: &mut ArrayBoard, from: &str, to: &str) -> UndoInfo {
+    let mv = legal_moves(array_board)
+        .into_iter()
+        .find(|mv| mv.from.to_string() == from && mv.to.to_string() == to)
+        .unwrap();
+    array_board.make_move(mv)
+}
+
+#[test]
+fn knight_shuffle_repeats_the_start_position() {
+    let mut array_board = ArrayBoard::new();
+    let mut undos = vec![];
+    for cycle in 1..=4 {
+        for (from, to) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")] {
+            undos.push(play(&mut array_board, from, to));
+        }
+        assert_eq!(array_board.repetition_count(), cycle + 1);
+        let expected = match cycle {
+            1 => GameOutcome::InProgress,
+            2 | 3 => GameOutcome::Draw(DrawReason::ThreefoldRepetition),
+            _ => GameOutcome::Draw(DrawReason::FivefoldRepetition),
+        };
+        assert_eq!(game_outcome(&array_board), expected, "after {cycle} cycles");
+    }
+
+    for _ in 0..4 {
+        array_board.unmake_move(undos.pop().unwrap());
+    }
+    assert_eq!(array_board.repetition_count(), 4);
+    assert_eq!(game_outcome(&array_board), GameOutcome::Draw(DrawReason::ThreefoldRepetition));
+    for _ in 0..8 {
+        array_board.unmake_move(undos.pop().unwrap());
+    }
+    assert_eq!(array_board.repetition_count(), 2);
+    assert_eq!(game_outcome(&array_board), GameOutcome::InProgress);
+}
+
+#[test]
+fn hundredth_quiet_halfmove_is_a_draw() {
+    let mut array_board = position(&[("e1", White, King), ("h1", White, Rook), ("e8", Black, King)], White, 99);
+    assert_eq!(game_outcome(&array_board), GameOutcome::InProgress);
+    play(&mut array_board, "e1", "d1");
+    assert_eq!(array_board.halfmove_clock, 100);
+    assert_eq!(game_outcome(&array_board), GameOutcome::Draw(DrawReason::FiftyMoveRule));
+}
+
+#[test]
+fn captures_and_pawn_moves_reset_the_halfmove_clock() {
+    let mut array_board = position(&[("e1", White, King), ("h1", White, Rook), ("e8", Black, King), ("h5", Black, Rook)], White, 99);
+    play(&mut array_board, "h1", "h5");
+    assert_eq!(array_board.halfmove_clock, 0);
+    assert_eq!(game_outcome(&array_board), GameOutcome::InProgress);
+
+    let mut array_board = position(&[("e1", White, King), ("h1", White, Rook), ("e8", Black, King), ("a2", White, Pawn)], White, 99);
+    play(&mut array_board, "a2", "a3");
+    assert_eq!(array_board.halfmove_clock, 0);
+    assert_eq!(game_outcome(&array_board), GameOutcome::InProgress);
+}
+
+#[test]
+fn lone_minor_pieces_are_insufficient_material() {
+    let draw = GameOutcome::Draw(DrawReason::InsufficientMaterial);
+    let kings = [("e1", White, King), ("e8", Black, King)];
+    let outcome = |extra: &[(&str, PieceColour, PieceType)]| game_outcome(&position(&[&kings[..], extra].concat(), White, 0));
+    assert_eq!(outcome(&[]), draw);
+    assert_eq!(outcome(&[("c1", White, Bishop)]), draw);
+    assert_eq!(outcome(&[("b1", White, Knight)]), draw);
+    // c1 and f8 are both dark squares
+    assert_eq!(outcome(&[("c1", White, Bishop), ("f8", Black, Bishop)]), draw);
+    // c1 is dark and c8 is light
+    assert_eq!(outcome(&[("c1", White, Bishop), ("c8", Black, Bishop)]), GameOutcome::InProgress);
+    assert_eq!(outcome(&[("b1", White, Knight), ("b8", Black, Knight)]), GameOutcome::InProgress);
+}
+
+#[test]
+fn checkmate_beats_the_fifty_move_rule() {
+    let pawns = [("f7", Black, Pawn), ("g7", Black, Pawn), ("h7", Black, Pawn)];
+    let mut array_board = position(&[&[("g1", White, King), ("a1", White, Rook), ("g8", Black, King)], &pawns[..]].concat(), White, 99);
+    play(&mut array_board, "a1", "a8");
+    assert_eq!(array_board.halfmove_clock, 100);
+    assert_eq!(game_outcome(&array_board), GameOutcome::Checkmate { winner: White });
+}
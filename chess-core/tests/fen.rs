@@ -0,0 +1,147 @@
+use chess_core::{ArrayBoard, FenError, PieceColour, Square, START_FEN};
+
+const POSITIONS: [&str; 6] = [
+    START_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+fn fen_error(fen: &str) -> FenError {
+    ArrayBoard::from_fen(fen).unwrap_err()
+}
+
+#[test]
+fn round_trips_reference_positions() {
+    for fen in POSITIONS {
+        assert_eq!(ArrayBoard::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn move_counters_are_optional() {
+    let array_board = ArrayBoard::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap();
+    assert_eq!(array_board.to_fen(), START_FEN);
+}
+
+#[test]
+fn rejects_wrong_field_count() {
+    assert_eq!(fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0"), FenError::WrongFieldCount(5));
+}
+
+#[test]
+fn rejects_wrong_rank_count() {
+    assert_eq!(fen_error("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), FenError::WrongRankCount(7));
+}
+
+#[test]
+fn rejects_ranks_of_the_wrong_length() {
+    assert!(matches!(
+        fen_error("rnbqkbnr/pppppppp/7/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        FenError::BadRank { rank: 5, .. }
+    ));
+    assert!(matches!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1"),
+        FenError::BadRank { rank: 0, .. }
+    ));
+    // far too long to count in a u8 without stopping at the edge of the board
+    let error = fen_error(&format!("rnbqkbnr/pppppppp/{}/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "p".repeat(264)));
+    assert!(matches!(error, FenError::BadRank { rank: 5, .. }), "{error:?}");
+    assert!(error.to_string().contains("more than 8 squares"), "{error}");
+}
+
+#[test]
+fn rejects_adjacent_digits() {
+    let error = fen_error("rnbqkbnr/pppppppp/44/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert!(matches!(error, FenError::BadRank { rank: 5, .. }), "{error:?}");
+    assert!(error.to_string().contains("two digits in a row"), "{error}");
+}
+
+#[test]
+fn rejects_digits_outside_one_to_eight() {
+    for rank_text in ["9", "0", "08"] {
+        let fen = format!("rnbqkbnr/pppppppp/{rank_text}/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let error = fen_error(&fen);
+        assert!(matches!(error, FenError::BadRank { rank: 5, .. }), "{fen}: {error:?}");
+        assert!(error.to_string().contains("must be 1 to 8"), "{error}");
+    }
+}
+
+#[test]
+fn rejects_invalid_piece() {
+    assert_eq!(fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1"), FenError::InvalidPiece('X'));
+}
+
+#[test]
+fn rejects_invalid_side_to_move() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"),
+        FenError::InvalidSideToMove("x".to_string())
+    );
+}
+
+#[test]
+fn rejects_invalid_castling() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1"),
+        FenError::InvalidCastling("KQkx".to_string())
+    );
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KK - 0 1"),
+        FenError::InvalidCastling("KK".to_string())
+    );
+}
+
+#[test]
+fn rejects_invalid_en_passant() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq z9 0 1"),
+        FenError::InvalidEnPassant("z9".to_string())
+    );
+    // no black pawn stands on e5 to have just moved two squares
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 1"),
+        FenError::InvalidEnPassant("e6".to_string())
+    );
+}
+
+#[test]
+fn rejects_invalid_halfmove_clock() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - -1 1"),
+        FenError::InvalidHalfmoveClock("-1".to_string())
+    );
+}
+
+#[test]
+fn rejects_invalid_fullmove_number() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0"),
+        FenError::InvalidFullmoveNumber("0".to_string())
+    );
+}
+
+#[test]
+fn rejects_wrong_king_count() {
+    assert_eq!(
+        fen_error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQQBNR w kq - 0 1"),
+        FenError::KingCount { colour: PieceColour::White, count: 0 }
+    );
+    assert_eq!(
+        fen_error("rnbkkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1"),
+        FenError::KingCount { colour: PieceColour::Black, count: 2 }
+    );
+}
+
+#[test]
+fn rejects_pawn_on_back_rank() {
+    assert_eq!(fen_error("4k2P/8/8/8/8/8/8/4K3 w - - 0 1"), FenError::PawnOnBackRank("h8".parse::<Square>().unwrap()));
+}
+
+#[test]
+fn rejects_opponent_in_check() {
+    assert_eq!(fen_error("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1"), FenError::OpponentInCheck);
+    assert_eq!(fen_error("4k3/8/8/8/8/8/8/r3K3 b - - 0 1"), FenError::OpponentInCheck);
+}
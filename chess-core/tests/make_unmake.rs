@@ -0,0 +1,28 @@
+use chess_core::{legal_moves, ArrayBoard};
+
+// Every move from these positions and the replies to it must unmake back to the same FEN,
+// covering captures, en passant, castling (and the loss of castling rights) and promotions
+const POSITIONS: [&str; 3] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+];
+
+#[test]
+fn unmake_restores_the_position() {
+    for fen in POSITIONS {
+        let mut array_board = ArrayBoard::from_fen(fen).unwrap();
+        for mv in legal_moves(&array_board) {
+            let undo = array_board.make_move(mv);
+            let after_fen = array_board.to_fen();
+            for reply in legal_moves(&array_board) {
+                let reply_undo = array_board.make_move(reply);
+                array_board.unmake_move(reply_undo);
+                assert_eq!(array_board.to_fen(), after_fen, "unmaking {reply} after {mv}");
+            }
+            array_board.unmake_move(undo);
+            assert_eq!(array_board.to_fen(), fen, "unmaking {mv}");
+            assert_eq!(array_board.history.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use chess_core::{legal_moves, ArrayBoard, MoveParseError, START_FEN};
+
+// SAN of each legal move from this FEN, sorted
+fn all_san(fen: &str) -> Vec<String> {
+    let array_board = ArrayBoard::from_fen(fen).unwrap();
+    let mut san: Vec<String> = legal_moves(&array_board).iter().map(|mv| mv.to_san(&array_board)).collect();
+    san.sort();
+    san
+}
+
+fn san_of(fen: &str, uci: &str) -> String {
+    let array_board = ArrayBoard::from_fen(fen).unwrap();
+    array_board.parse_uci(uci).unwrap().to_san(&array_board)
+}
+
+#[test]
+fn san_round_trips_every_legal_move() {
+    let fens = [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ];
+    for fen in fens {
+        let array_board = ArrayBoard::from_fen(fen).unwrap();
+        for mv in legal_moves(&array_board) {
+            assert_eq!(array_board.parse_san(&mv.to_san(&array_board)), Ok(mv), "{fen}");
+            assert_eq!(array_board.parse_uci(&mv.to_uci()), Ok(mv), "{fen}");
+        }
+    }
+}
+
+#[test]
+fn disambiguation() {
+    // knights on b1 and f3 can both reach d2, rooks on e1 and e3 can both reach e2
+    let fen = "k7/8/8/8/8/4RN2/8/1N2RK2 w - - 0 1";
+    assert_eq!(san_of(fen, "b1d2"), "Nbd2");
+    assert_eq!(san_of(fen, "e1e2"), "R1e2");
+    assert_eq!(san_of(fen, "e3e2"), "R3e2");
+    // three queens that share files and ranks need the whole square
+    let fen = "7k/8/8/8/2Q1Q3/8/2Q5/K7 w - - 0 1";
+    assert_eq!(san_of(fen, "c4d3"), "Qc4d3");
+}
+
+#[test]
+fn captures_promotions_castling_and_checks() {
+    assert_eq!(san_of("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2", "e4d5"), "exd5");
+    assert_eq!(san_of("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", "e5f6"), "exf6");
+    assert_eq!(san_of("8/4P3/8/8/8/8/k7/7K w - - 0 1", "e7e8q"), "e8=Q");
+    assert_eq!(san_of("3r4/4P3/8/8/8/8/k7/7K w - - 0 1", "e7d8n"), "exd8=N");
+    assert_eq!(san_of("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1g1"), "O-O");
+    assert_eq!(san_of("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1c1"), "O-O-O");
+    assert_eq!(san_of("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8"), "Ra8+");
+    assert_eq!(san_of("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2", "d8h4"), "Qh4#");
+}
+
+#[test]
+fn parse_san_is_tolerant_of_marks() {
+    let array_board = ArrayBoard::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    assert_eq!(array_board.parse_san("0-0").unwrap().to_uci(), "e1g1");
+    assert_eq!(array_board.parse_san("O-O-O!?").unwrap().to_uci(), "e1c1");
+    assert_eq!(array_board.parse_san("Rxa8+").unwrap().to_uci(), "a1a8");
+    let array_board = ArrayBoard::from_fen("8/4P3/8/8/8/8/k7/7K w - - 0 1").unwrap();
+    assert_eq!(array_board.parse_san("e8Q").unwrap().to_uci(), "e7e8q");
+}
+
+#[test]
+fn parse_errors() {
+    let array_board = ArrayBoard::new();
+    assert_eq!(array_board.parse_san("e5"), Err(MoveParseError::IllegalMove("e5".to_string())));
+    assert_eq!(array_board.parse_san("Zz9"), Err(MoveParseError::InvalidSyntax("Zz9".to_string())));
+    assert_eq!(array_board.parse_uci("e2e5"), Err(MoveParseError::IllegalMove("e2e5".to_string())));
+    assert_eq!(array_board.parse_uci("e2"), Err(MoveParseError::InvalidSyntax("e2".to_string())));
+    let array_board = ArrayBoard::from_fen("k7/8/8/8/8/4RN2/8/1N2RK2 w - - 0 1").unwrap();
+    assert_eq!(array_board.parse_san("Nd2"), Err(MoveParseError::AmbiguousMove("Nd2".to_string())));
+}
+
+#[test]
+fn every_start_move_has_a_plain_name() {
+    let san = all_san(START_FEN);
+    assert_eq!(san.len(), 20);
+    assert!(san.contains(&"Nf3".to_string()) && san.contains(&"e4".to_string()));
+}
@@ -0,0 +1,103 @@
+use chess_core::pgn::result_tag;
+use chess_core::{game_outcome, ArrayBoard, PgnGame};
+
+fn play(game: &mut PgnGame, moves: &[&str]) {
+    let mut array_board = game.final_position();
+    for san in moves {
+        let mv = array_board.parse_san(san).unwrap();
+        array_board.make_move(mv);
+        game.moves.push(mv);
+    }
+}
+
+#[test]
+fn exports_seven_tag_roster_and_movetext() {
+    let mut game = PgnGame::new(ArrayBoard::new());
+    game.set_tag("Event", "Club \"blitz\" night");
+    play(&mut game, &["f3", "e5", "g4", "Qh4"]);
+    game.set_tag("Result", result_tag(&game_outcome(&game.final_position())));
+    assert_eq!(
+        game.to_pgn(),
+        "[Event \"Club \\\"blitz\\\" night\"]\n\
+         [Site \"?\"]\n\
+         [Date \"????.??.??\"]\n\
+         [Round \"?\"]\n\
+         [White \"?\"]\n\
+         [Black \"?\"]\n\
+         [Result \"0-1\"]\n\
+         \n\
+         1. f3 e5 2. g4 Qh4# 0-1\n"
+    );
+}
+
+#[test]
+fn non_standard_start_adds_setup_and_fen() {
+    let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+    let mut game = PgnGame::new(ArrayBoard::from_fen(fen).unwrap());
+    play(&mut game, &["Kd7", "e4"]);
+    let pgn = game.to_pgn();
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]\n"));
+    assert!(pgn.ends_with("\n12... Kd7 13. e4 *\n"));
+}
+
+#[test]
+fn long_movetext_wraps() {
+    let mut game = PgnGame::new(ArrayBoard::new());
+    for _ in 0..10 {
+        play(&mut game, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+    }
+    let pgn = game.to_pgn();
+    assert!(pgn.lines().all(|line| line.len() < 80));
+    assert!(pgn.ends_with(" *\n"));
+}
+
+#[test]
+fn import_skips_comments_nags_variations_and_escapes() {
+    let text = "\
+% exported by some tool
+[Event \"Casual \\\"game\\\"\"]
+[White \"Anderssen\"]
+[Result \"1-0\"]
+
+1. e4 {King's pawn} e5 2. Nf3 $1 Nc6 (2... d6 3. d4 (3. Bc4)) 3. Bb5!? ; the Spanish
+a6 1-0
+
+[Event \"Second\"]
+[SetUp \"1\"]
+[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 12\"]
+
+12... Kd7 13. e4 *
+";
+    let games = chess_core::parse_pgn(text).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].tag("Event"), Some("Casual \"game\""));
+    assert_eq!(games[0].tag("White"), Some("Anderssen"));
+    assert_eq!(games[0].tag("Result"), Some("1-0"));
+    let moves: Vec<String> = games[0].moves.iter().map(|mv| mv.to_uci()).collect();
+    assert_eq!(moves, ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6"]);
+    assert_eq!(games[1].final_position().to_fen(), "8/3k4/8/8/4P3/8/8/4K3 b - e3 0 13");
+}
+
+#[test]
+fn export_then_import_round_trips() {
+    let mut game = PgnGame::new(ArrayBoard::new());
+    play(&mut game, &["e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5", "d4", "c6", "Nf3", "Nf6", "Bc4", "Bf5", "O-O"]);
+    let games = chess_core::parse_pgn(&game.to_pgn()).unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].moves, game.moves);
+    assert_eq!(games[0].tags, game.tags);
+}
+
+#[test]
+fn import_errors_have_line_and_column() {
+    let error = chess_core::parse_pgn("[Event \"x\"]\n\n1. e4 e5 2. Ke3 *\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 13));
+    assert_eq!(error.to_string(), "line 3, column 13: 'Ke3' is not a legal move in this position");
+
+    let error = chess_core::parse_pgn("1. e4 {never closed\n e5 *").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 7));
+    let error = chess_core::parse_pgn("1. e4 (1. d4 *").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 7));
+    let error = chess_core::parse_pgn("[Event \"x]\n1. e4 *").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 1));
+}
@@ -0,0 +1,63 @@
+use chess_core::zobrist::zobrist_from_scratch;
+use chess_core::{legal_moves, ArrayBoard, START_FEN};
+
+const POSITIONS: [&str; 3] = [
+    START_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+];
+
+// Walks the move tree checking the incremental key after every make and unmake
+fn check_keys(array_board: &mut ArrayBoard, depth: u32) {
+    assert_eq!(array_board.zobrist_key(), zobrist_from_scratch(array_board), "{}", array_board.to_fen());
+    if depth == 0 {
+        return;
+    }
+    let key = array_board.zobrist_key();
+    for mv in legal_moves(array_board) {
+        let undo = array_board.make_move(mv);
+        check_keys(array_board, depth - 1);
+        array_board.unmake_move(undo);
+        assert_eq!(array_board.zobrist_key(), key, "unmaking {mv}");
+    }
+}
+
+fn play(fen: &str, moves: &[&str]) -> ArrayBoard {
+    let mut array_board = ArrayBoard::from_fen(fen).unwrap();
+    for name in moves {
+        let mv = legal_moves(&array_board)
+            .into_iter()
+            .find(|mv| mv.to_string() == *name)
+            .unwrap();
+        array_board.make_move(mv);
+    }
+    array_board
+}
+
+#[test]
+fn incremental_key_matches_recomputation() {
+    for fen in POSITIONS {
+        check_keys(&mut ArrayBoard::from_fen(fen).unwrap(), 3);
+    }
+}
+
+#[test]
+fn transpositions_share_a_key() {
+    let first = play(START_FEN, &["g1f3", "g8f6", "b1c3"]);
+    let second = play(START_FEN, &["b1c3", "g8f6", "g1f3"]);
+    assert_eq!(first.zobrist_key(), second.zobrist_key());
+}
+
+#[test]
+fn key_covers_side_castling_and_en_passant() {
+    let key = |fen: &str| ArrayBoard::from_fen(fen).unwrap().zobrist_key();
+    let base = "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1";
+    assert_ne!(key(base), key("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1"));
+    assert_ne!(key(base), key("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b Kkq - 0 1"));
+    assert_ne!(key(base), key("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"));
+    // an en passant tile no pawn can capture onto does not change the position
+    assert_eq!(
+        key("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"),
+        key("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+    );
+}
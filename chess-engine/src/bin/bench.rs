@@ -0,0 +1,59 @@
+use std::process::exit;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use chess_core::{ArrayBoard, START_FEN};
+use chess_engine::tt::DEFAULT_HASH_MB;
+use chess_engine::{search, SearchLimits, SearchOptions, TranspositionTable};
+
+const USAGE: &str = "usage: bench [depth] [threads]";
+const DEFAULT_DEPTH: u32 = 4;
+// An opening, a busy middlegame, a quiet middlegame and an endgame
+const POSITIONS: [&str; 4] = [
+    START_FEN,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQ - 0 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+];
+
+// Searches each position to the same depth with the transposition table and move ordering switched
+// on and off, to show how many nodes each saves: cargo run --release -p chess-engine --bin bench -- 5
+// Given a thread count as well, the times show what the extra threads gain.
+fn main() {
+    let number = |i: usize, default: u32| match std::env::args().nth(i) {
+        None => default,
+        Some(number) => number.parse::<u32>().unwrap_or_else(|_| {
+            eprintln!("{USAGE}");
+            exit(1);
+        }),
+    };
+    let depth = number(1, DEFAULT_DEPTH);
+    let threads = number(2, 1) as usize;
+    let configurations = [
+        ("plain alpha-beta", false, false),
+        ("transposition table", true, false),
+        ("move ordering", false, true),
+        ("both", true, true),
+    ];
+
+    println!("depth {depth}, {threads} thread(s)");
+    println!("{:<22}{:>14}{:>10}", "", "nodes", "ms");
+    for (name, transposition_table, move_ordering) in configurations {
+        let options = SearchOptions {
+            threads,
+            transposition_table,
+            move_ordering,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut nodes = 0;
+        for fen in POSITIONS {
+            let array_board = ArrayBoard::from_fen(fen).unwrap();
+            // a fresh table each time so no position gets a head start from the one before
+            let tt = TranspositionTable::new(DEFAULT_HASH_MB);
+            let result = search(&array_board, &SearchLimits::depth(depth), &options, &tt, &AtomicBool::new(false), |_info| ());
+            nodes += result.nodes;
+        }
+        println!("{name:<22}{nodes:>14}{:>10}", start.elapsed().as_millis());
+    }
+}
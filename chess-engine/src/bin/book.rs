@@ -0,0 +1,73 @@
+use std::process::exit;
+
+use chess_core::parse_pgn;
+use chess_engine::book::BookBuilder;
+
+const USAGE: &str = "usage: book <PGN file>... [--plies N] [--min-games N] [--out <book file>]";
+const DEFAULT_PLIES: usize = 16;
+const DEFAULT_MIN_GAMES: u32 = 1;
+const DEFAULT_OUT: &str = "book.bin";
+
+// Builds a Polyglot opening book from the first moves of every game in some PGN files:
+// cargo run --release -p chess-engine --bin book -- games/*.pgn --plies 20 --min-games 2
+// The book it writes is played from with the UCI option BookFile or the game's --book flag.
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut pgn_paths = vec![];
+    let mut plies = DEFAULT_PLIES;
+    let mut min_games = DEFAULT_MIN_GAMES;
+    let mut out = DEFAULT_OUT.to_string();
+    let mut i = 0;
+    while i < args.len() {
+        let value = || {
+            args.get(i + 1).cloned().unwrap_or_else(|| {
+                eprintln!("{} needs a value after it\n{USAGE}", args[i]);
+                exit(1);
+            })
+        };
+        let number = |value: String| {
+            value.parse::<u32>().unwrap_or_else(|_| {
+                eprintln!("{} needs a number\n{USAGE}", args[i]);
+                exit(1);
+            })
+        };
+        match args[i].as_str() {
+            "--plies" => plies = number(value()) as usize,
+            "--min-games" => min_games = number(value()),
+            "--out" => out = value(),
+            path => {
+                pgn_paths.push(path.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    if pgn_paths.is_empty() {
+        eprintln!("{USAGE}");
+        exit(1);
+    }
+
+    let mut builder = BookBuilder::new(plies);
+    let mut games = 0;
+    for path in &pgn_paths {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+            eprintln!("could not read {path}: {error}");
+            exit(1);
+        });
+        let parsed = parse_pgn(&text).unwrap_or_else(|error| {
+            eprintln!("{path}: {error}");
+            exit(1);
+        });
+        for game in &parsed {
+            builder.add_game(game);
+        }
+        games += parsed.len();
+    }
+    let book = builder.build(min_games);
+    if let Err(error) = std::fs::write(&out, book.to_bytes()) {
+        eprintln!("could not write {out}: {error}");
+        exit(1);
+    }
+    println!("{games} games, {} book entries written to {out}", book.len());
+}
@@ -0,0 +1,51 @@
+use std::process::exit;
+
+use chess_engine::endgame::Ending;
+use chess_engine::EndgameTables;
+
+const USAGE: &str = "usage: endgames [kqk] [krk] [kpk] [kbnk] [--out <endgame file>]";
+const DEFAULT_OUT: &str = "endgames.egt";
+
+// Generates the endgame tables, all of them when none are named, and writes them to one file:
+// cargo run --release -p chess-engine --bin endgames -- kpk --out kpk.egt
+// The file is used with the UCI option EndgameFile or the game's --endgames flag.
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut endings = vec![];
+    let mut out = DEFAULT_OUT.to_string();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--out" => {
+                out = args.get(i + 1).cloned().unwrap_or_else(|| {
+                    eprintln!("--out needs a value after it\n{USAGE}");
+                    exit(1);
+                });
+                i += 2;
+            }
+            name => {
+                endings.push(Ending::from_name(name).unwrap_or_else(|| {
+                    eprintln!("no table for {name}\n{USAGE}");
+                    exit(1);
+                }));
+                i += 1;
+            }
+        }
+    }
+    if endings.is_empty() {
+        endings = Ending::ALL.to_vec();
+    }
+
+    let tables = EndgameTables::generate(&endings, |ending, time| {
+        println!("{} generated in {:.1}s", ending.name(), time.as_secs_f64());
+    });
+    for ending in tables.endings() {
+        println!("{}: longest mate {} moves", ending.name(), tables.longest_mate(ending).unwrap_or(0));
+    }
+    let bytes = tables.to_bytes();
+    if let Err(error) = std::fs::write(&out, &bytes) {
+        eprintln!("could not write {out}: {error}");
+        exit(1);
+    }
+    println!("{} bytes written to {out}", bytes.len());
+}
@@ -0,0 +1,75 @@
+use std::process::exit;
+
+use chess_engine::eval::load_params;
+use chess_engine::tune::{fit_scaling, mean_squared_error, parse_training_set, tune};
+use chess_engine::EvalParams;
+
+const USAGE: &str = "usage: tune <positions file> [--iterations N] [--start <params file>] [--out <params file>]";
+const DEFAULT_ITERATIONS: usize = 1000;
+const DEFAULT_OUT: &str = "eval-params.txt";
+// How often progress is printed
+const REPORT_EVERY: usize = 50;
+
+// Texel tuning of the evaluation against positions from played games, each line a FEN and the game's result:
+// cargo run --release -p chess-engine --bin tune -- positions.epd --iterations 2000
+// The parameters it writes are loaded with the UCI option EvalFile or the game's --eval flag.
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(positions_path) = args.first().filter(|arg| !arg.starts_with("--")) else {
+        eprintln!("{USAGE}");
+        exit(1);
+    };
+    let flag = |name: &str| {
+        let i = args.iter().position(|arg| arg == name)?;
+        let Some(value) = args.get(i + 1) else {
+            eprintln!("{name} needs a value after it\n{USAGE}");
+            exit(1);
+        };
+        Some(value.clone())
+    };
+    let iterations = flag("--iterations").map_or(DEFAULT_ITERATIONS, |iterations| {
+        iterations.parse().unwrap_or_else(|_| {
+            eprintln!("--iterations needs a number\n{USAGE}");
+            exit(1);
+        })
+    });
+    let out = flag("--out").unwrap_or(DEFAULT_OUT.to_string());
+    let start = match flag("--start") {
+        Some(path) => load_params(&path).unwrap_or_else(|error| {
+            eprintln!("{error}");
+            exit(1);
+        }),
+        None => EvalParams::default(),
+    };
+
+    let text = std::fs::read_to_string(positions_path).unwrap_or_else(|error| {
+        eprintln!("could not read {positions_path}: {error}");
+        exit(1);
+    });
+    let positions = parse_training_set(&text).unwrap_or_else(|error| {
+        eprintln!("{positions_path}, {error}");
+        exit(1);
+    });
+    if positions.is_empty() {
+        eprintln!("{positions_path} has no positions in it");
+        exit(1);
+    }
+
+    let start_weights: Vec<f64> = start.weights().into_iter().map(f64::from).collect();
+    let scaling = fit_scaling(&positions, &start_weights);
+    println!(
+        "{} positions, scaling {scaling:.3}, starting error {:.6}",
+        positions.len(),
+        mean_squared_error(&positions, &start_weights, scaling)
+    );
+    let tuned = tune(&positions, &start, scaling, iterations, |iteration, error| {
+        if iteration % REPORT_EVERY == 0 || iteration == iterations {
+            println!("iteration {iteration}: error {error:.6}");
+        }
+    });
+    if let Err(error) = std::fs::write(&out, tuned.to_text()) {
+        eprintln!("could not write {out}: {error}");
+        exit(1);
+    }
+    println!("wrote {out}");
+}
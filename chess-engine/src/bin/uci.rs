@@ -0,0 +1,17 @@
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+
+use chess_engine::uci::UciEngine;
+
+// Point a chess GUI at `cargo run --release -p chess-engine --bin uci`
+fn main() {
+    let mut engine = UciEngine::new(Arc::new(Mutex::new(std::io::stdout())));
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else { break; };
+        if !engine.handle_command(&line) {
+            return;
+        }
+    }
+    // the GUI went away without saying quit
+    engine.handle_command("quit");
+}
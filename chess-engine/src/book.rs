@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+
+use chess_core::{legal_moves, ArrayBoard, Move, PgnGame, PieceColour, PieceType, Square};
+
+use crate::polyglot::polyglot_key;
+
+// key (8 bytes), move (2), weight (2) and learn (4), all big-endian
+const ENTRY_BYTES: usize = 16;
+
+// One entry of a Polyglot book: a position, a move from it and how often that move should be picked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    // to square (bits 0-5), from square (6-11) and promotion (12-14), castling written as the king taking its rook
+    pub mv: u16,
+    pub weight: u16,
+    // part of the format, but nothing here reads or changes it
+    pub learn: u32,
+}
+
+// A Polyglot .bin opening book, its entries sorted by key so a position's moves sit next to each other
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    entries: Vec<BookEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookError(pub String);
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for BookError {}
+
+impl OpeningBook {
+    // Sorted the way Polyglot writes books: by key, then the heaviest move first
+    pub fn new(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by_key(|entry| (entry.key, Reverse(entry.weight), entry.mv));
+        OpeningBook { entries }
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<OpeningBook, BookError> {
+        if !bytes.len().is_multiple_of(ENTRY_BYTES) {
+            return Err(BookError(format!("{} bytes is not a whole number of {ENTRY_BYTES} byte entries", bytes.len())));
+        }
+        let entries = bytes
+            .chunks_exact(ENTRY_BYTES)
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                mv: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        Ok(OpeningBook::new(entries))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.entries.len() * ENTRY_BYTES);
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.key.to_be_bytes());
+            bytes.extend_from_slice(&entry.mv.to_be_bytes());
+            bytes.extend_from_slice(&entry.weight.to_be_bytes());
+            bytes.extend_from_slice(&entry.learn.to_be_bytes());
+        }
+        bytes
+    }
+    pub fn entries(&self) -> &[BookEntry] {
+        &self.entries
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    // The book's moves for this position with their weights. Entries that match no legal move,
+    // from a key collision or a broken book, are left out.
+    pub fn moves(&self, array_board: &ArrayBoard) -> Vec<(Move, u16)> {
+        let key = polyglot_key(array_board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let legal = legal_moves(array_board);
+        self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| legal.iter().find(|mv| encode_move(mv) == entry.mv).map(|mv| (*mv, entry.weight)))
+            .collect()
+    }
+    // A book move picked with chances in proportion to the weights, `random` deciding which.
+    // None once the game has left the book, or when every move it has here weighs nothing.
+    pub fn pick(&self, array_board: &ArrayBoard, random: u64) -> Option<Move> {
+        let moves = self.moves(array_board);
+        let total: u64 = moves.iter().map(|(_mv, weight)| *weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut target = random % total;
+        for (mv, weight) in moves {
+            if target < weight as u64 {
+                return Some(mv);
+            }
+            target -= weight as u64;
+        }
+        None
+    }
+    // pick, with a different game every time
+    pub fn choose(&self, array_board: &ArrayBoard) -> Option<Move> {
+        self.pick(array_board, RandomState::new().build_hasher().finish())
+    }
+}
+
+// Counts the moves played in a collection of games, up to `max_plies` into each, to write them out as a book
+pub struct BookBuilder {
+    max_plies: usize,
+    // (key, move) to the points the move scored and the games it was played in
+    moves: HashMap<(u64, u16), (u32, u32)>,
+}
+
+impl BookBuilder {
+    pub fn new(max_plies: usize) -> Self {
+        BookBuilder {
+            max_plies,
+            moves: HashMap::new(),
+        }
+    }
+    // Scores each move as Polyglot's own book maker does: 2 points for the side that went on to win, 1 each for a draw.
+    // Games without a result count as played but score nothing.
+    pub fn add_game(&mut self, game: &PgnGame) {
+        let mut board = game.start.clone();
+        for mv in game.moves.iter().take(self.max_plies) {
+            let points = match (game.tag("Result"), board.turn) {
+                (Some("1-0"), PieceColour::White) | (Some("0-1"), PieceColour::Black) => 2,
+                (Some("1/2-1/2"), _) => 1,
+                _ => 0,
+            };
+            let (total, games) = self.moves.entry((polyglot_key(&board), encode_move(mv))).or_default();
+            *total += points;
+            *games += 1;
+            board.make_move(*mv);
+        }
+    }
+    // Moves played in fewer than `min_games` games, or that only ever lost, are left out.
+    // Weights are scaled down together if the biggest would not fit in 16 bits.
+    pub fn build(&self, min_games: u32) -> OpeningBook {
+        let kept: Vec<(&(u64, u16), u32)> = self
+            .moves
+            .iter()
+            .filter(|(_key, (points, games))| *points > 0 && *games >= min_games)
+            .map(|(key, (points, _games))| (key, *points))
+            .collect();
+        let most = kept.iter().map(|(_key, points)| *points).max().unwrap_or(0);
+        let scale = (most as f64 / u16::MAX as f64).max(1.);
+        let entries = kept
+            .into_iter()
+            .map(|(&(key, mv), points)| BookEntry {
+                key,
+                mv,
+                weight: ((points as f64 / scale).round() as u16).max(1),
+                learn: 0,
+            })
+            .collect();
+        OpeningBook::new(entries)
+    }
+}
+
+pub fn load_book(path: &str) -> Result<OpeningBook, BookError> {
+    let bytes = std::fs::read(path).map_err(|error| BookError(format!("could not read {path}: {error}")))?;
+    OpeningBook::from_bytes(&bytes).map_err(|error| BookError(format!("{path} is not a Polyglot book, {error}")))
+}
+
+// A move the way Polyglot stores it
+pub fn encode_move(mv: &Move) -> u16 {
+    // castling is written as the king moving onto its own rook's corner
+    let to = if mv.is_castling {
+        Square::new(if mv.to.file() == 6 { 7 } else { 0 }, mv.to.rank())
+    } else {
+        mv.to
+    };
+    let promotion = match mv.promotion {
+        None => 0,
+        Some(PieceType::Knight) => 1,
+        Some(PieceType::Bishop) => 2,
+        Some(PieceType::Rook) => 3,
+        Some(_) => 4,
+    };
+    to.index() as u16 | (mv.from.index() as u16) << 6 | promotion << 12
+}
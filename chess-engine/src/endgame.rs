@@ -0,0 +1,368 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use chess_core::bitboard::squares;
+use chess_core::{legal_moves, ArrayBoard, Move, PieceColour, PieceType, Square};
+
+use crate::retrograde;
+
+// Start of every endgame file, followed by a version byte
+const MAGIC: &[u8; 8] = b"CHESSEGT";
+const VERSION: u8 = 1;
+// The white king's squares in the a1-d1-d4 triangle, where the other seven ways of turning or
+// mirroring the board can always bring it
+const TRIANGLE_SQUARES: usize = 10;
+
+// The endings with tables: a king and these pieces against a bare king
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ending {
+    Kqk,
+    Krk,
+    Kpk,
+    Kbnk,
+}
+
+// How a position ends with perfect play, for the side to move, in plies until mate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndgameValue {
+    Win(u32),
+    Loss(u32),
+    Draw,
+}
+
+// A position from one of the endings, seen with the side that has the extra pieces as white
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EndgamePosition {
+    pub white_to_move: bool,
+    pub white_king: Square,
+    pub black_king: Square,
+    // white's other pieces in the order Ending::pieces lists them, only as many as the ending has
+    pub pieces: [Square; 2],
+}
+
+// Distance-to-mate tables, one byte a position: 0 for a draw (or a position that cannot happen),
+// otherwise one more than the plies to mate. An even number of plies means the side to move gets mated.
+// Only one of each set of positions that are the same but for turning or mirroring the board is kept.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct EndgameTables {
+    tables: Vec<(Ending, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndgameError(pub String);
+
+impl fmt::Display for EndgameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for EndgameError {}
+
+// The tables run to megabytes, so only what they cover is shown
+impl fmt::Debug for EndgameTables {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.endings()).finish()
+    }
+}
+
+impl Ending {
+    // In the order they are generated, KPK promotes into the two before it
+    pub const ALL: [Ending; 4] = [Ending::Kqk, Ending::Krk, Ending::Kpk, Ending::Kbnk];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ending::Kqk => "KQK",
+            Ending::Krk => "KRK",
+            Ending::Kpk => "KPK",
+            Ending::Kbnk => "KBNK",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Ending> {
+        Ending::ALL.into_iter().find(|ending| ending.name().eq_ignore_ascii_case(name))
+    }
+    // The strong side's pieces besides its king
+    pub fn pieces(&self) -> &'static [PieceType] {
+        match self {
+            Ending::Kqk => &[PieceType::Queen],
+            Ending::Krk => &[PieceType::Rook],
+            Ending::Kpk => &[PieceType::Pawn],
+            Ending::Kbnk => &[PieceType::Bishop, PieceType::Knight],
+        }
+    }
+    // Positions in the stored table. With a pawn only the mirror left to right is used,
+    // the pawn kept on the a to d files, otherwise the white king is kept in the a1-d1-d4 triangle.
+    fn compact_size(&self) -> usize {
+        match self {
+            Ending::Kpk => 2 * 64 * 64 * 32,
+            _ => 2 * TRIANGLE_SQUARES * 64usize.pow(1 + self.pieces().len() as u32),
+        }
+    }
+    // Where a position is in the stored table
+    fn compact_index(&self, position: &EndgamePosition) -> usize {
+        let count = self.pieces().len();
+        if *self == Ending::Kpk {
+            let mirror = position.pieces[0].file() > 3;
+            let [white_king, black_king, pawn] =
+                [position.white_king, position.black_king, position.pieces[0]].map(|square| transform(square, mirror as u8));
+            return ((position.white_to_move as usize * 64 + white_king.index()) * 64 + black_king.index()) * 32
+                + pawn.rank() as usize * 4
+                + pawn.file() as usize;
+        }
+        let symmetry = triangle_symmetry(position.white_king);
+        let white_king = transform(position.white_king, symmetry);
+        let triangle = [0, 4, 7, 9][white_king.rank() as usize] + (white_king.file() - white_king.rank()) as usize;
+        let mut index = (position.white_to_move as usize * TRIANGLE_SQUARES + triangle) * 64
+            + transform(position.black_king, symmetry).index();
+        for square in &position.pieces[..count] {
+            index = index * 64 + transform(*square, symmetry).index();
+        }
+        index
+    }
+    // The position stored at `index`, the inverse of compact_index
+    fn compact_position(&self, mut index: usize) -> EndgamePosition {
+        let mut pieces = [Square::from_index(0); 2];
+        if *self == Ending::Kpk {
+            pieces[0] = Square::new((index % 4) as u8, (index / 4 % 8) as u8);
+            index /= 32;
+        } else {
+            for square in pieces[..self.pieces().len()].iter_mut().rev() {
+                *square = Square::from_index(index % 64);
+                index /= 64;
+            }
+        }
+        let black_king = Square::from_index(index % 64);
+        index /= 64;
+        let (white_king, white_to_move) = if *self == Ending::Kpk {
+            (Square::from_index(index % 64), index / 64 == 1)
+        } else {
+            let triangle = index % TRIANGLE_SQUARES;
+            let rank = [0, 4, 7, 9].iter().rposition(|start| *start <= triangle).unwrap();
+            (Square::new((triangle - [0, 4, 7, 9][rank] + rank) as u8, rank as u8), index / TRIANGLE_SQUARES == 1)
+        };
+        EndgamePosition {
+            white_to_move,
+            white_king,
+            black_king,
+            pieces,
+        }
+    }
+}
+
+impl EndgamePosition {
+    // The ending a board is in, and the position as the tables see it. None for any other material,
+    // and while castling rights are left, which the tables know nothing about.
+    pub fn from_board(array_board: &ArrayBoard) -> Option<(Ending, EndgamePosition)> {
+        let rights = &array_board.castling_rights;
+        let any_rights = rights.white_kingside || rights.white_queenside || rights.black_kingside || rights.black_queenside;
+        if array_board.occupied().count_ones() > 4 || any_rights {
+            return None;
+        }
+        let strong = if array_board.colour_occupancy(&PieceColour::White).count_ones() > 1 {
+            PieceColour::White
+        } else {
+            PieceColour::Black
+        };
+        let weak = strong.opposite();
+        if array_board.colour_occupancy(&weak).count_ones() != 1 {
+            return None;
+        }
+        // black with the extra pieces is turned around to be white
+        let seen = |square: Square| if strong.is_white() { square } else { Square::new(square.file(), 7 - square.rank()) };
+        let sorted = |piece_types: &mut dyn Iterator<Item = PieceType>| {
+            let mut indices: Vec<usize> = piece_types.map(|piece_type| piece_type.index()).collect();
+            indices.sort();
+            indices
+        };
+        let extra_pieces = sorted(&mut array_board.pieces().filter(|(_square, piece)| piece.colour == strong).map(|(_square, piece)| piece.piece_type).filter(|piece_type| !piece_type.is_king()));
+        let ending = Ending::ALL.into_iter().find(|ending| sorted(&mut ending.pieces().iter().copied()) == extra_pieces)?;
+        let mut pieces = [Square::from_index(0); 2];
+        for (i, piece_type) in ending.pieces().iter().enumerate() {
+            pieces[i] = seen(squares(array_board.pieces_of(&strong, *piece_type)).next()?);
+        }
+        let king = |colour: &PieceColour| squares(array_board.pieces_of(colour, PieceType::King)).next().map(seen);
+        Some((
+            ending,
+            EndgamePosition {
+                white_to_move: array_board.turn == strong,
+                white_king: king(&strong)?,
+                black_king: king(&weak)?,
+                pieces,
+            },
+        ))
+    }
+}
+
+impl EndgameValue {
+    fn from_byte(byte: u8) -> EndgameValue {
+        match byte {
+            0 => EndgameValue::Draw,
+            _ if byte.is_multiple_of(2) => EndgameValue::Win(byte as u32 - 1),
+            _ => EndgameValue::Loss(byte as u32 - 1),
+        }
+    }
+}
+
+impl EndgameTables {
+    // Works out the tables for `endings` by retrograde analysis, calling `on_generated` as each one is done.
+    // KPK needs KQK and KRK for its promotions, so asking for it generates them too.
+    pub fn generate(endings: &[Ending], mut on_generated: impl FnMut(Ending, Duration)) -> Self {
+        let mut tables = EndgameTables::default();
+        for ending in Ending::ALL {
+            let needed = endings.contains(&ending)
+                || (endings.contains(&Ending::Kpk) && matches!(ending, Ending::Kqk | Ending::Krk));
+            if !needed {
+                continue;
+            }
+            let start = Instant::now();
+            let full = retrograde::generate(ending, &tables);
+            let compact = (0..ending.compact_size())
+                .map(|index| full[retrograde::full_index(ending, &ending.compact_position(index))])
+                .collect();
+            tables.tables.push((ending, compact));
+            on_generated(ending, start.elapsed());
+        }
+        tables
+    }
+    pub fn endings(&self) -> Vec<Ending> {
+        self.tables.iter().map(|(ending, _table)| *ending).collect()
+    }
+    // The stored byte for a position of an ending these tables have
+    pub(crate) fn byte(&self, ending: Ending, position: &EndgamePosition) -> Option<u8> {
+        let (_ending, table) = self.tables.iter().find(|(table_ending, _table)| *table_ending == ending)?;
+        Some(table[ending.compact_index(position)])
+    }
+    pub fn value(&self, ending: Ending, position: &EndgamePosition) -> Option<EndgameValue> {
+        self.byte(ending, position).map(EndgameValue::from_byte)
+    }
+    // The most moves the strong side ever needs to mate in an ending, from a position where it is to move
+    pub fn longest_mate(&self, ending: Ending) -> Option<u32> {
+        let (_ending, table) = self.tables.iter().find(|(table_ending, _table)| *table_ending == ending)?;
+        table
+            .iter()
+            .filter_map(|byte| match EndgameValue::from_byte(*byte) {
+                EndgameValue::Win(plies) => Some(plies.div_ceil(2)),
+                _ => None,
+            })
+            .max()
+    }
+    // None when the board is not in an ending these tables have
+    pub fn probe(&self, array_board: &ArrayBoard) -> Option<EndgameValue> {
+        let (ending, position) = EndgamePosition::from_board(array_board)?;
+        self.value(ending, &position)
+    }
+    // The move that mates soonest when winning, holds the draw, or puts mate off longest when losing,
+    // with what the position is worth
+    pub fn best_move(&self, array_board: &ArrayBoard) -> Option<(Move, EndgameValue)> {
+        self.probe(array_board)?;
+        let mut board = array_board.clone();
+        let mut best: Option<(Move, EndgameValue)> = None;
+        for mv in legal_moves(array_board) {
+            let undo = board.make_move(mv);
+            // taking the last piece that could mate leaves a draw no table covers
+            let reply = self.probe(&board).or_else(|| board.is_insufficient_material().then_some(EndgameValue::Draw));
+            board.unmake_move(undo);
+            let value = match reply {
+                Some(EndgameValue::Loss(plies)) => EndgameValue::Win(plies + 1),
+                Some(EndgameValue::Win(plies)) => EndgameValue::Loss(plies + 1),
+                Some(EndgameValue::Draw) => EndgameValue::Draw,
+                None => continue,
+            };
+            if best.is_none_or(|(_mv, best_value)| rank(value) > rank(best_value)) {
+                best = Some((mv, value));
+            }
+        }
+        best
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.push(self.tables.len() as u8);
+        for (ending, table) in &self.tables {
+            bytes.push(ending.name().len() as u8);
+            bytes.extend_from_slice(ending.name().as_bytes());
+            bytes.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(table);
+        }
+        bytes
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<EndgameTables, EndgameError> {
+        let error = |message: &str| Err(EndgameError(message.to_string()));
+        if bytes.len() < MAGIC.len() + 2 || bytes[..MAGIC.len()] != *MAGIC {
+            return error("not an endgame table file");
+        }
+        if bytes[MAGIC.len()] != VERSION {
+            return error("written by another version of the endgame generator");
+        }
+        let mut rest = &bytes[MAGIC.len() + 2..];
+        let mut tables = EndgameTables::default();
+        for _ in 0..bytes[MAGIC.len() + 1] {
+            let Some((&name_length, after)) = rest.split_first() else {
+                return error("cut short");
+            };
+            let name_length = name_length as usize;
+            if after.len() < name_length + 4 {
+                return error("cut short");
+            }
+            let name = String::from_utf8_lossy(&after[..name_length]);
+            let Some(ending) = Ending::from_name(&name) else {
+                return Err(EndgameError(format!("unknown ending {name}")));
+            };
+            let size = u32::from_be_bytes(after[name_length..name_length + 4].try_into().unwrap()) as usize;
+            if size != ending.compact_size() || after.len() < name_length + 4 + size {
+                return Err(EndgameError(format!("the {name} table is the wrong size")));
+            }
+            let table = &after[name_length + 4..name_length + 4 + size];
+            tables.tables.push((ending, table.to_vec()));
+            rest = &after[name_length + 4 + size..];
+        }
+        Ok(tables)
+    }
+}
+
+pub fn load_endgames(path: &str) -> Result<EndgameTables, EndgameError> {
+    let bytes = std::fs::read(path).map_err(|error| EndgameError(format!("could not read {path}: {error}")))?;
+    EndgameTables::from_bytes(&bytes).map_err(|error| EndgameError(format!("{path}: {error}")))
+}
+
+// Orders values from the mover's point of view: quicker wins, then draws, then slower losses
+fn rank(value: EndgameValue) -> i64 {
+    match value {
+        EndgameValue::Win(plies) => 1000 - plies as i64,
+        EndgameValue::Draw => 0,
+        EndgameValue::Loss(plies) => -1000 + plies as i64,
+    }
+}
+
+// Which of the eight symmetries brings the square into the a1-d1-d4 triangle
+fn triangle_symmetry(square: Square) -> u8 {
+    let mut symmetry = 0;
+    let (mut file, mut rank) = (square.file(), square.rank());
+    if file > 3 {
+        symmetry |= 1;
+        file = 7 - file;
+    }
+    if rank > 3 {
+        symmetry |= 2;
+        rank = 7 - rank;
+    }
+    if rank > file {
+        symmetry |= 4;
+    }
+    symmetry
+}
+
+// Mirrors files (1), then ranks (2), then swaps files and ranks (4)
+fn transform(square: Square, symmetry: u8) -> Square {
+    let (mut file, mut rank) = (square.file(), square.rank());
+    if symmetry & 1 != 0 {
+        file = 7 - file;
+    }
+    if symmetry & 2 != 0 {
+        rank = 7 - rank;
+    }
+    if symmetry & 4 != 0 {
+        (file, rank) = (rank, file);
+    }
+    Square::new(file, rank)
+}
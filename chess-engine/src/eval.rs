@@ -0,0 +1,446 @@
+use std::fmt;
+
+use chess_core::bitboard::{bishop_attacks, bit, king_attacks, knight_attacks, queen_attacks, rook_attacks, squares};
+use chess_core::{ArrayBoard, Bitboard, PieceColour, PieceType, Square};
+
+// The starting piece-square tables, in centipawns on top of the piece's value. They are laid out the way
+// white sees the board, rank 8 first, and are mirrored for black.
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     50,  50,  50,  50,  50,  50,  50,  50,
+     10,  10,  20,  30,  30,  20,  10,  10,
+      5,   5,  10,  25,  25,  10,   5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10,  10,  10,  10,  10,   5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      0,   0,   0,   5,   5,   0,   0,   0,
+];
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+// While there is enough material left for an attack the king hides behind its pawns...
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+// ...and once it is gone the king comes out to the middle
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+    -50, -40, -30, -20, -20, -30, -40, -50,
+    -30, -20, -10,   0,   0, -10, -20, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  30,  40,  40,  30, -10, -30,
+    -30, -10,  20,  30,  30,  20, -10, -30,
+    -30, -30,   0,   0,   0,   0, -30, -30,
+    -50, -30, -30, -30, -30, -30, -30, -50,
+];
+// Game phase counted from the pieces left: knights and bishops 1, rooks 2, queens 4
+const OPENING_PHASE: i32 = 24;
+const FILE_A: Bitboard = 0x0101_0101_0101_0101;
+
+// Where each group of weights starts in the flat list `weights` gives, for the tuner
+const PIECE_VALUES: usize = 0;
+const MIDDLEGAME_TABLES: usize = PIECE_VALUES + 6;
+const ENDGAME_TABLES: usize = MIDDLEGAME_TABLES + 6 * 64;
+const MOBILITY: usize = ENDGAME_TABLES + 6 * 64;
+const KING_SHIELD: usize = MOBILITY + 6;
+const KING_ATTACK: usize = KING_SHIELD + 1;
+const DOUBLED_PAWN: usize = KING_ATTACK + 1;
+const ISOLATED_PAWN: usize = DOUBLED_PAWN + 1;
+const PASSED_PAWN: usize = ISOLATED_PAWN + 1;
+pub const WEIGHT_COUNT: usize = PASSED_PAWN + 8;
+
+// Everything the evaluation weighs, in centipawns. Arrays by piece are indexed by PieceType::index,
+// and the tables are laid out like the ones above.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalParams {
+    pub piece_values: [i32; 6],
+    // with every piece on the board, faded into the endgame tables as they come off
+    pub middlegame_tables: [[i32; 64]; 6],
+    pub endgame_tables: [[i32; 64]; 6],
+    // per square a knight, bishop, rook or queen attacks that its own side does not stand on
+    pub mobility: [i32; 6],
+    // king safety, counted in the middlegame only: per pawn of its own just in front of the king...
+    pub king_shield: i32,
+    // ...and per attack on the squares around the enemy king
+    pub king_attack: i32,
+    // pawn structure: per pawn more than one on a file, per pawn with none of its own on the files beside it,
+    // and per pawn no enemy pawn can stop, by how far up the board it is
+    pub doubled_pawn: i32,
+    pub isolated_pawn: i32,
+    pub passed_pawn: [i32; 8],
+}
+
+// Which part of the game a term counts in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Taper {
+    Always,
+    Middlegame,
+    Endgame,
+}
+
+// A parameter file that could not be read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamsError(pub String);
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+// The usual centipawn values, which move ordering goes by and the evaluation starts from
+pub fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        let mut params = EvalParams {
+            piece_values: [0; 6],
+            middlegame_tables: [[0; 64]; 6],
+            endgame_tables: [[0; 64]; 6],
+            mobility: [0; 6],
+            king_shield: 10,
+            king_attack: 5,
+            doubled_pawn: -10,
+            isolated_pawn: -15,
+            passed_pawn: [0, 5, 10, 20, 35, 60, 100, 0],
+        };
+        for piece_type in PieceType::ALL {
+            let index = piece_type.index();
+            params.piece_values[index] = piece_value(piece_type);
+            let (middlegame, endgame, mobility) = match piece_type {
+                PieceType::Pawn => (PAWN_TABLE, PAWN_TABLE, 0),
+                PieceType::Knight => (KNIGHT_TABLE, KNIGHT_TABLE, 4),
+                PieceType::Bishop => (BISHOP_TABLE, BISHOP_TABLE, 4),
+                PieceType::Rook => (ROOK_TABLE, ROOK_TABLE, 2),
+                PieceType::Queen => (QUEEN_TABLE, QUEEN_TABLE, 1),
+                PieceType::King => (KING_MIDDLEGAME_TABLE, KING_ENDGAME_TABLE, 0),
+            };
+            params.middlegame_tables[index] = middlegame;
+            params.endgame_tables[index] = endgame;
+            params.mobility[index] = mobility;
+        }
+        params
+    }
+}
+
+impl EvalParams {
+    // How good the position is for the side to move, in centipawns
+    pub fn evaluate(&self, array_board: &ArrayBoard) -> i32 {
+        let phase = game_phase(array_board);
+        let (mut always, mut middlegame, mut endgame) = (0, 0, 0);
+        terms(array_board, |index, taper, count| {
+            let value = self.weight(index) * count;
+            match taper {
+                Taper::Always => always += value,
+                Taper::Middlegame => middlegame += value,
+                Taper::Endgame => endgame += value,
+            }
+        });
+        let score = always + (middlegame * phase + endgame * (OPENING_PHASE - phase)) / OPENING_PHASE;
+        if array_board.turn.is_white() {
+            score
+        } else {
+            -score
+        }
+    }
+    // Every weight in one list: piece values, middlegame tables, endgame tables, mobility, king shield,
+    // king attack, doubled pawn, isolated pawn, passed pawns
+    pub fn weights(&self) -> Vec<i32> {
+        (0..WEIGHT_COUNT).map(|index| self.weight(index)).collect()
+    }
+    pub fn set_weights(&mut self, weights: &[i32]) {
+        for (index, weight) in weights.iter().enumerate().take(WEIGHT_COUNT) {
+            *self.weight_mut(index) = *weight;
+        }
+    }
+    // One named group of numbers after another, e.g. "king_shield 10". Tables are written as eight rows.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "# chess-engine evaluation parameters, in centipawns\n\
+             # per piece groups go pawn, king, queen, rook, bishop, knight; tables start at a8 from white's side\n",
+        );
+        for (name, start, len) in groups() {
+            text.push_str(&name);
+            for (i, index) in (start..start + len).enumerate() {
+                text.push(if len == 64 && i % 8 == 0 { '\n' } else { ' ' });
+                text.push_str(&format!("{:4}", self.weight(index)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+    // Reads what to_text writes. Groups left out keep their default values and # starts a comment.
+    pub fn from_text(text: &str) -> Result<EvalParams, ParamsError> {
+        let mut params = EvalParams::default();
+        let groups = groups();
+        let mut tokens = text.lines().enumerate().flat_map(|(line, text)| {
+            text.split('#')
+                .next()
+                .unwrap_or("")
+                .split_whitespace()
+                .map(move |token| (line + 1, token))
+        });
+        while let Some((line, name)) = tokens.next() {
+            let Some((_name, start, len)) = groups.iter().find(|(group, _start, _len)| group == name) else {
+                return Err(ParamsError(format!("line {line}: unknown parameter '{name}'")));
+            };
+            for index in *start..start + len {
+                let Some((line, token)) = tokens.next() else {
+                    return Err(ParamsError(format!("{name} needs {len} numbers")));
+                };
+                *params.weight_mut(index) = token.parse().map_err(|_| ParamsError(format!("line {line}: '{token}' in {name} is not a whole number")))?;
+            }
+        }
+        Ok(params)
+    }
+    fn weight(&self, index: usize) -> i32 {
+        match index {
+            PIECE_VALUES..MIDDLEGAME_TABLES => self.piece_values[index - PIECE_VALUES],
+            MIDDLEGAME_TABLES..ENDGAME_TABLES => self.middlegame_tables[(index - MIDDLEGAME_TABLES) / 64][index % 64],
+            ENDGAME_TABLES..MOBILITY => self.endgame_tables[(index - ENDGAME_TABLES) / 64][index % 64],
+            MOBILITY..KING_SHIELD => self.mobility[index - MOBILITY],
+            KING_SHIELD => self.king_shield,
+            KING_ATTACK => self.king_attack,
+            DOUBLED_PAWN => self.doubled_pawn,
+            ISOLATED_PAWN => self.isolated_pawn,
+            _ => self.passed_pawn[index - PASSED_PAWN],
+        }
+    }
+    fn weight_mut(&mut self, index: usize) -> &mut i32 {
+        match index {
+            PIECE_VALUES..MIDDLEGAME_TABLES => &mut self.piece_values[index - PIECE_VALUES],
+            MIDDLEGAME_TABLES..ENDGAME_TABLES => &mut self.middlegame_tables[(index - MIDDLEGAME_TABLES) / 64][index % 64],
+            ENDGAME_TABLES..MOBILITY => &mut self.endgame_tables[(index - ENDGAME_TABLES) / 64][index % 64],
+            MOBILITY..KING_SHIELD => &mut self.mobility[index - MOBILITY],
+            KING_SHIELD => &mut self.king_shield,
+            KING_ATTACK => &mut self.king_attack,
+            DOUBLED_PAWN => &mut self.doubled_pawn,
+            ISOLATED_PAWN => &mut self.isolated_pawn,
+            _ => &mut self.passed_pawn[index - PASSED_PAWN],
+        }
+    }
+}
+
+// Reads a parameter file as written by the tune binary
+pub fn load_params(path: &str) -> Result<EvalParams, ParamsError> {
+    let text = std::fs::read_to_string(path).map_err(|error| ParamsError(format!("could not read {path}: {error}")))?;
+    EvalParams::from_text(&text).map_err(|error| ParamsError(format!("{path}, {error}")))
+}
+
+// The evaluation as a sum of weight times coefficient, from white's point of view, with the middlegame and
+// endgame terms already faded by the game phase. The score is this dotted with `weights`, give or take rounding,
+// which is what lets the tuner work out how each weight moves it.
+pub fn features(array_board: &ArrayBoard) -> Vec<(usize, f64)> {
+    let phase = game_phase(array_board) as f64 / OPENING_PHASE as f64;
+    let mut features: Vec<(usize, f64)> = vec![];
+    terms(array_board, |index, taper, count| {
+        let coefficient = count as f64
+            * match taper {
+                Taper::Always => 1.,
+                Taper::Middlegame => phase,
+                Taper::Endgame => 1. - phase,
+            };
+        match features.iter_mut().find(|(feature, _coefficient)| *feature == index) {
+            Some((_feature, sum)) => *sum += coefficient,
+            None => features.push((index, coefficient)),
+        }
+    });
+    features.retain(|(_index, coefficient)| *coefficient != 0.);
+    features
+}
+
+// Calls `term` with the index of each weight that applies, when it applies and how many times for white
+// (negative for black). Both the evaluation and the tuner's features are built from this.
+fn terms(array_board: &ArrayBoard, mut term: impl FnMut(usize, Taper, i32)) {
+    let occupied = array_board.occupied();
+    for colour in [PieceColour::White, PieceColour::Black] {
+        let sign = if colour.is_white() { 1 } else { -1 };
+        let own = array_board.colour_occupancy(&colour);
+        let enemy_king_zone = squares(array_board.pieces_of(&colour.opposite(), PieceType::King))
+            .next()
+            .map_or(0, |king| king_attacks(king) | bit(king));
+
+        for piece_type in PieceType::ALL {
+            let type_index = piece_type.index();
+            for square in squares(array_board.pieces_of(&colour, piece_type)) {
+                let table_index = table_index(&colour, square);
+                term(PIECE_VALUES + type_index, Taper::Always, sign);
+                term(MIDDLEGAME_TABLES + type_index * 64 + table_index, Taper::Middlegame, sign);
+                term(ENDGAME_TABLES + type_index * 64 + table_index, Taper::Endgame, sign);
+                let attacks = match piece_type {
+                    PieceType::Knight => knight_attacks(square),
+                    PieceType::Bishop => bishop_attacks(square, occupied),
+                    PieceType::Rook => rook_attacks(square, occupied),
+                    PieceType::Queen => queen_attacks(square, occupied),
+                    PieceType::Pawn | PieceType::King => continue,
+                };
+                term(
+                    MOBILITY + type_index,
+                    Taper::Always,
+                    sign * (attacks & !own).count_ones() as i32,
+                );
+                term(
+                    KING_ATTACK,
+                    Taper::Middlegame,
+                    sign * (attacks & enemy_king_zone).count_ones() as i32,
+                );
+            }
+        }
+
+        let pawns = array_board.pieces_of(&colour, PieceType::Pawn);
+        let enemy_pawns = array_board.pieces_of(&colour.opposite(), PieceType::Pawn);
+        for file in 0..8 {
+            let on_file = (pawns & FILE_A << file).count_ones() as i32;
+            if on_file > 1 {
+                term(DOUBLED_PAWN, Taper::Always, sign * (on_file - 1));
+            }
+        }
+        for square in squares(pawns) {
+            if pawns & neighbour_files(square.file()) & !(FILE_A << square.file()) == 0 {
+                term(ISOLATED_PAWN, Taper::Always, sign);
+            }
+            if enemy_pawns & neighbour_files(square.file()) & ranks_ahead(&colour, square.rank()) == 0 {
+                let rank = if colour.is_white() { square.rank() } else { 7 - square.rank() };
+                term(PASSED_PAWN + rank as usize, Taper::Always, sign);
+            }
+        }
+        if let Some(king) = squares(array_board.pieces_of(&colour, PieceType::King)).next() {
+            // pawns on the two ranks in front of the king
+            let beyond = (king.rank() as i8 + 2 * sign as i8).clamp(0, 7) as u8;
+            let shield = pawns & neighbour_files(king.file()) & ranks_ahead(&colour, king.rank()) & !ranks_ahead(&colour, beyond);
+            term(KING_SHIELD, Taper::Middlegame, sign * shield.count_ones() as i32);
+        }
+    }
+}
+
+// From OPENING_PHASE with every piece on the board down to 0 with only kings and pawns
+fn game_phase(array_board: &ArrayBoard) -> i32 {
+    let phase: i32 = array_board
+        .pieces()
+        .map(|(_square, piece)| match piece.piece_type {
+            PieceType::Knight | PieceType::Bishop => 1,
+            PieceType::Rook => 2,
+            PieceType::Queen => 4,
+            PieceType::Pawn | PieceType::King => 0,
+        })
+        .sum();
+    phase.min(OPENING_PHASE)
+}
+
+fn table_index(colour: &PieceColour, square: Square) -> usize {
+    // the tables start from rank 8, which is where black's own back rank lies after mirroring
+    let rank = if colour.is_white() { 7 - square.rank() } else { square.rank() };
+    rank as usize * 8 + square.file() as usize
+}
+
+// The file and the files either side of it
+fn neighbour_files(file: u8) -> Bitboard {
+    let file_bb = FILE_A << file;
+    let left = if file > 0 { file_bb >> 1 } else { 0 };
+    let right = if file < 7 { file_bb << 1 } else { 0 };
+    left | file_bb | right
+}
+
+// Every rank further up the board than `rank` as `colour` sees it
+fn ranks_ahead(colour: &PieceColour, rank: u8) -> Bitboard {
+    if colour.is_white() {
+        if rank == 7 {
+            0
+        } else {
+            !0 << ((rank + 1) * 8)
+        }
+    } else {
+        (1 << (rank * 8)) - 1
+    }
+}
+
+// Each group's name in the parameter file, where it starts in `weights` and how many numbers it has
+fn groups() -> Vec<(String, usize, usize)> {
+    let name = |piece_type: PieceType| format!("{piece_type:?}").to_lowercase();
+    let mut groups = vec![("piece_values".to_string(), PIECE_VALUES, 6)];
+    for piece_type in PieceType::ALL {
+        groups.push((
+            format!("middlegame_{}", name(piece_type)),
+            MIDDLEGAME_TABLES + piece_type.index() * 64,
+            64,
+        ));
+    }
+    for piece_type in PieceType::ALL {
+        groups.push((
+            format!("endgame_{}", name(piece_type)),
+            ENDGAME_TABLES + piece_type.index() * 64,
+            64,
+        ));
+    }
+    groups.extend([
+        ("mobility".to_string(), MOBILITY, 6),
+        ("king_shield".to_string(), KING_SHIELD, 1),
+        ("king_attack".to_string(), KING_ATTACK, 1),
+        ("doubled_pawn".to_string(), DOUBLED_PAWN, 1),
+        ("isolated_pawn".to_string(), ISOLATED_PAWN, 1),
+        ("passed_pawn".to_string(), PASSED_PAWN, 8),
+    ]);
+    groups
+}
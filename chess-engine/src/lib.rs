@@ -0,0 +1,21 @@
+#![deny(clippy::all)]
+
+// A computer player for chess-core boards: evaluation, search, opening books, endgame tables and the UCI protocol.
+
+pub mod book;
+pub mod endgame;
+pub mod eval;
+pub mod limits;
+pub mod polyglot;
+mod retrograde;
+pub mod search;
+pub mod tt;
+pub mod tune;
+pub mod uci;
+
+pub use book::OpeningBook;
+pub use endgame::EndgameTables;
+pub use eval::EvalParams;
+pub use limits::SearchLimits;
+pub use search::{search, SearchInfo, SearchOptions, SearchResult};
+pub use tt::TranspositionTable;
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use chess_core::PieceColour;
+
+// Kept back from the clock so a move still arrives when the budget is spent
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+// Without a moves-to-go count assume the game lasts this many more moves
+const MOVES_TO_GO: u32 = 30;
+
+// When a search has to stop, as given by the UCI `go` command. All unset means think until told to stop.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub infinite: bool,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        }
+    }
+    pub fn movetime(movetime: Duration) -> Self {
+        SearchLimits {
+            movetime: Some(movetime),
+            ..Default::default()
+        }
+    }
+    // How long the side to move may think, None if the search only ends on depth, nodes or stop
+    pub fn time_budget(&self, turn: &PieceColour) -> Option<Duration> {
+        if self.infinite {
+            return None;
+        }
+        if let Some(movetime) = self.movetime {
+            return Some(movetime.saturating_sub(MOVE_OVERHEAD).max(Duration::from_millis(1)));
+        }
+        let (time, increment) = if turn.is_white() { (self.wtime, self.winc) } else { (self.btime, self.binc) };
+        let time = time?;
+        let increment = increment.unwrap_or_default();
+        let budget = time / self.movestogo.unwrap_or(MOVES_TO_GO).max(1) + increment * 3 / 4;
+        Some(
+            budget
+                .min(time.saturating_sub(MOVE_OVERHEAD))
+                .max(Duration::from_millis(1)),
+        )
+    }
+}
@@ -0,0 +1,236 @@
+use chess_core::bitboard::{bishop_attacks, bit, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks, squares};
+use chess_core::{Bitboard, PieceColour, PieceType, Square};
+
+use crate::endgame::{EndgamePosition, EndgameTables, Ending};
+
+// Where a position is in the full table, every square for every piece, white's side to move first
+pub(crate) fn full_index(ending: Ending, position: &EndgamePosition) -> usize {
+    let mut index = (position.white_to_move as usize * 64 + position.white_king.index()) * 64 + position.black_king.index();
+    for square in &position.pieces[..ending.pieces().len()] {
+        index = index * 64 + square.index();
+    }
+    index
+}
+
+// Retrograde analysis: every mate is found first, then the positions one move before them, and so on
+// backwards a ply at a time. A position with white to move is won in n plies as soon as any move reaches
+// a black loss in n - 1. One with black to move is lost once every one of its moves has been found to
+// reach a white win, and the last of those to be found is the longest. Whatever is left at the end is a draw.
+// The result is the full table, a byte a position as EndgameTables stores them.
+pub(crate) fn generate(ending: Ending, tables: &EndgameTables) -> Vec<u8> {
+    let count = ending.pieces().len();
+    let size = 2 * 64usize.pow(2 + count as u32);
+    let mut values = vec![0u8; size];
+    // black's moves not yet known to lose, a capture never does as it leaves a draw
+    let mut remaining = vec![0u8; size];
+    let mut frontier = vec![];
+    // white wins reached by promoting, which come from the KQK and KRK tables, by the round they belong to
+    let mut promotions: Vec<Vec<usize>> = vec![];
+
+    for index in 0..size {
+        let position = position_at(ending, index);
+        if !is_legal(ending, &position) {
+            continue;
+        }
+        if position.white_to_move {
+            if let Some(plies) = promotion_win(ending, &position, tables) {
+                if promotions.len() <= plies {
+                    promotions.resize(plies + 1, vec![]);
+                }
+                promotions[plies].push(index);
+            }
+            continue;
+        }
+        let (moves, in_check) = black_moves(ending, &position);
+        remaining[index] = moves;
+        if moves == 0 && in_check {
+            values[index] = 1;
+            frontier.push(index);
+        }
+    }
+
+    let mut plies = 1;
+    while !frontier.is_empty() || plies < promotions.len() {
+        let mut next = vec![];
+        for &index in promotions.get(plies).into_iter().flatten() {
+            if values[index] == 0 {
+                values[index] = plies as u8 + 1;
+                next.push(index);
+            }
+        }
+        for index in frontier {
+            let position = position_at(ending, index);
+            if position.white_to_move {
+                // a white win: one more of black's moves is known to lose
+                for_each_black_unmove(ending, &position, |before| {
+                    let before = full_index(ending, &before);
+                    if values[before] == 0 && remaining[before] > 0 {
+                        remaining[before] -= 1;
+                        if remaining[before] == 0 {
+                            values[before] = plies as u8 + 1;
+                            next.push(before);
+                        }
+                    }
+                });
+            } else {
+                // a black loss: white wins by moving into it
+                for_each_white_unmove(ending, &position, |before| {
+                    let before = full_index(ending, &before);
+                    if values[before] == 0 {
+                        values[before] = plies as u8 + 1;
+                        next.push(before);
+                    }
+                });
+            }
+        }
+        frontier = next;
+        plies += 1;
+    }
+    values
+}
+
+fn position_at(ending: Ending, mut index: usize) -> EndgamePosition {
+    let mut pieces = [Square::from_index(0); 2];
+    for square in pieces[..ending.pieces().len()].iter_mut().rev() {
+        *square = Square::from_index(index % 64);
+        index /= 64;
+    }
+    EndgamePosition {
+        white_to_move: index / 4096 == 1,
+        white_king: Square::from_index(index / 64 % 64),
+        black_king: Square::from_index(index % 64),
+        pieces,
+    }
+}
+
+fn occupied(ending: Ending, position: &EndgamePosition) -> Bitboard {
+    position.pieces[..ending.pieces().len()]
+        .iter()
+        .fold(bit(position.white_king) | bit(position.black_king), |occupied, square| occupied | bit(*square))
+}
+
+// What white's pieces other than the king attack, leaving out the one at `captured` if black has just taken it
+fn piece_attacks(ending: Ending, position: &EndgamePosition, occupied: Bitboard, captured: Option<Square>) -> Bitboard {
+    ending
+        .pieces()
+        .iter()
+        .zip(position.pieces)
+        .filter(|(_piece_type, square)| captured != Some(*square))
+        .fold(0, |attacks, (piece_type, square)| attacks | white_attacks(*piece_type, square, occupied))
+}
+
+fn white_attacks(piece_type: PieceType, square: Square, occupied: Bitboard) -> Bitboard {
+    match piece_type {
+        PieceType::Pawn => pawn_attacks(&PieceColour::White, square),
+        PieceType::Knight => knight_attacks(square),
+        PieceType::Bishop => bishop_attacks(square, occupied),
+        PieceType::Rook => rook_attacks(square, occupied),
+        PieceType::Queen => queen_attacks(square, occupied),
+        PieceType::King => king_attacks(square),
+    }
+}
+
+fn is_legal(ending: Ending, position: &EndgamePosition) -> bool {
+    let count = ending.pieces().len();
+    let occupied = occupied(ending, position);
+    let pawn_on_back_rank = ending
+        .pieces()
+        .iter()
+        .zip(position.pieces)
+        .any(|(piece_type, square)| piece_type.is_pawn() && (square.rank() == 0 || square.rank() == 7));
+    occupied.count_ones() as usize == 2 + count
+        && king_attacks(position.white_king) & bit(position.black_king) == 0
+        && !pawn_on_back_rank
+        // black cannot be left in check with white to move
+        && !(position.white_to_move && piece_attacks(ending, position, occupied, None) & bit(position.black_king) != 0)
+}
+
+// How many legal moves black has, and whether black is in check
+fn black_moves(ending: Ending, position: &EndgamePosition) -> (u8, bool) {
+    let occupied = occupied(ending, position);
+    let in_check = piece_attacks(ending, position, occupied, None) & bit(position.black_king) != 0;
+    let mut moves = 0;
+    for to in squares(king_attacks(position.black_king) & !king_attacks(position.white_king)) {
+        // sliders see through the square the king leaves
+        let after = occupied & !bit(position.black_king) | bit(to);
+        if piece_attacks(ending, position, after, Some(to)) & bit(to) == 0 {
+            moves += 1;
+        }
+    }
+    (moves, in_check)
+}
+
+// White wins by promoting to a queen or rook that black then cannot escape, the soonest in plies
+fn promotion_win(ending: Ending, position: &EndgamePosition, tables: &EndgameTables) -> Option<usize> {
+    let pawn = position.pieces[0];
+    if ending != Ending::Kpk || pawn.rank() != 6 {
+        return None;
+    }
+    let promotion_square = Square::new(pawn.file(), 7);
+    if occupied(ending, position) & bit(promotion_square) != 0 {
+        return None;
+    }
+    let after = EndgamePosition {
+        white_to_move: false,
+        pieces: [promotion_square, promotion_square],
+        ..*position
+    };
+    [Ending::Kqk, Ending::Krk]
+        .into_iter()
+        .filter_map(|promoted| tables.byte(promoted, &after))
+        // black to move and losing is stored as an odd byte, one more than the plies to mate
+        .filter(|byte| byte % 2 == 1)
+        .map(|byte| byte as usize)
+        .min()
+}
+
+// Every position with black to move whose king move leads here
+fn for_each_black_unmove(ending: Ending, position: &EndgamePosition, mut f: impl FnMut(EndgamePosition)) {
+    let occupied = occupied(ending, position);
+    for from in squares(king_attacks(position.black_king) & !occupied & !king_attacks(position.white_king)) {
+        f(EndgamePosition {
+            white_to_move: false,
+            black_king: from,
+            ..*position
+        });
+    }
+}
+
+// Every position with white to move where some white move leads here
+fn for_each_white_unmove(ending: Ending, position: &EndgamePosition, mut f: impl FnMut(EndgamePosition)) {
+    let occupied = occupied(ending, position);
+    let mut visit = |before: EndgamePosition| {
+        if is_legal(ending, &before) {
+            f(before);
+        }
+    };
+    for from in squares(king_attacks(position.white_king) & !occupied & !king_attacks(position.black_king)) {
+        visit(EndgamePosition {
+            white_to_move: true,
+            white_king: from,
+            ..*position
+        });
+    }
+    for (i, piece_type) in ending.pieces().iter().enumerate() {
+        let square = position.pieces[i];
+        let sources = match piece_type {
+            // one square back, or two from the second rank
+            PieceType::Pawn if square.rank() >= 2 => {
+                let one_back = bit(Square::new(square.file(), square.rank() - 1)) & !occupied;
+                let two_back = if square.rank() == 3 && one_back != 0 { bit(Square::new(square.file(), 1)) & !occupied } else { 0 };
+                one_back | two_back
+            }
+            PieceType::Pawn => 0,
+            // every other piece moves back the way it came
+            _ => white_attacks(*piece_type, square, occupied) & !occupied,
+        };
+        for from in squares(sources) {
+            let mut before = EndgamePosition {
+                white_to_move: true,
+                ..*position
+            };
+            before.pieces[i] = from;
+            visit(before);
+        }
+    }
+}
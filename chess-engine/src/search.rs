@@ -0,0 +1,438 @@
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chess_core::{legal_moves, legal_moves_mut, ArrayBoard, Move};
+
+use crate::book::OpeningBook;
+use crate::endgame::{EndgameTables, EndgameValue};
+use crate::eval::{piece_value, EvalParams};
+use crate::limits::SearchLimits;
+use crate::tt::{Bound, HashMove, TranspositionTable, TtEntry};
+
+// Scores at or beyond MATE_SCORE - MAX_PLY are mates, counted down by one per ply to the mate
+pub const MATE_SCORE: i32 = 30_000;
+pub const MAX_PLY: u32 = 128;
+const MAX_DEPTH: u32 = 64;
+pub const MAX_THREADS: usize = 256;
+const INFINITY: i32 = 32_000;
+// The clock is only read every this many nodes
+const TIME_CHECK_INTERVAL: u64 = 1024;
+// Move ordering scores: the hash move, then captures and promotions, then the killers, then quiet moves by history
+const HASH_MOVE_SCORE: i32 = 3_000_000;
+const CAPTURE_SCORE: i32 = 2_000_000;
+const KILLER_SCORES: [i32; 2] = [1_500_000, 1_400_000];
+// History scores are halved once one reaches this, so they stay below the killers and old ones fade
+const HISTORY_LIMIT: i32 = 1_000_000;
+
+// How the search runs: how many threads, what it evaluates positions with, the opening book and endgame
+// tables it plays from if any, and which of its speed-ups are used (all of them unless a benchmark is comparing)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub threads: usize,
+    pub params: Arc<EvalParams>,
+    pub book: Option<Arc<OpeningBook>>,
+    pub endgames: Option<Arc<EndgameTables>>,
+    pub transposition_table: bool,
+    pub move_ordering: bool,
+}
+
+// Reported after every completed iteration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: i32,
+    pub nodes: u64,
+    pub time: Duration,
+    // how full the transposition table is, in thousandths
+    pub hashfull: u32,
+    // the best line found, starting with the move to play
+    pub pv: Vec<Move>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    // None only when the side to move has no legal move
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            threads: 1,
+            params: Arc::new(EvalParams::default()),
+            book: None,
+            endgames: None,
+            transposition_table: true,
+            move_ordering: true,
+        }
+    }
+}
+
+// A table value as a search score, mates counted from the root like the ones the search finds
+fn endgame_score(value: EndgameValue, ply: u32) -> i32 {
+    match value {
+        EndgameValue::Win(plies) => MATE_SCORE - (ply + plies) as i32,
+        EndgameValue::Loss(plies) => -MATE_SCORE + (ply + plies) as i32,
+        EndgameValue::Draw => 0,
+    }
+}
+
+// Moves until mate for a mate score (negative when the side to move is the one getting mated)
+pub fn mate_in(score: i32) -> Option<i32> {
+    if score.abs() < MATE_SCORE - MAX_PLY as i32 {
+        return None;
+    }
+    let plies = MATE_SCORE - score.abs();
+    let moves = (plies + 1) / 2;
+    Some(if score > 0 { moves } else { -moves })
+}
+
+// Iterative deepening: searches one ply deeper each time until a limit is reached or `stop` is set,
+// calling `on_info` after every depth. The best move of the last completed depth is played.
+// `tt` keeps what it learns for the next search, so it should live as long as the game.
+//
+// With more than one thread this is Lazy SMP: helper threads search the same position at the same time,
+// half of them a ply deeper, and only share their work through `tt`. They fill it with results the main
+// thread then finds ready. The main thread alone keeps to the limits, reports and picks the move, and
+// the helpers stop when it does. One thread searches exactly the same tree every time.
+//
+// While the position is in the opening book a book move is played straight away, with a depth of 0,
+// unless the search is `infinite` and so is there to analyse the position. The same goes for a position
+// the endgame tables have, which gets their best move and its exact score, and inside the tree their
+// positions are scored straight from the tables.
+pub fn search(
+    array_board: &ArrayBoard,
+    limits: &SearchLimits,
+    options: &SearchOptions,
+    tt: &TranspositionTable,
+    stop: &AtomicBool,
+    mut on_info: impl FnMut(&SearchInfo),
+) -> SearchResult {
+    let start = Instant::now();
+    let budget = limits.time_budget(&array_board.turn);
+    let max_depth = limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+    tt.new_search();
+    // something to play even if the first iteration is cut short
+    let mut result = SearchResult {
+        best_move: legal_moves(array_board).first().copied(),
+        score: 0,
+        depth: 0,
+        nodes: 0,
+    };
+    if result.best_move.is_none() {
+        return result;
+    }
+    let book = options.book.as_ref().filter(|_book| !limits.infinite);
+    if let Some(book_move) = book.and_then(|book| book.choose(array_board)) {
+        result.best_move = Some(book_move);
+        return result;
+    }
+    let endgames = options.endgames.as_ref().filter(|_endgames| !limits.infinite);
+    if let Some((endgame_move, value)) = endgames.and_then(|endgames| endgames.best_move(array_board)) {
+        result.best_move = Some(endgame_move);
+        result.score = endgame_score(value, 0);
+        return result;
+    }
+
+    let main_finished = AtomicBool::new(false);
+    let total_nodes = AtomicU64::new(0);
+    thread::scope(|scope| {
+        for helper in 1..options.threads.clamp(1, MAX_THREADS) {
+            let mut searcher = Searcher::new(array_board, options, tt, &main_finished, &total_nodes);
+            scope.spawn(move || {
+                for depth in (1 + helper as u32 % 2)..=max_depth {
+                    searcher.negamax(depth, 0, -INFINITY, INFINITY, &mut vec![]);
+                    if searcher.aborted {
+                        break;
+                    }
+                }
+                searcher.flush_nodes();
+            });
+        }
+
+        let mut searcher = Searcher::new(array_board, options, tt, stop, &total_nodes);
+        searcher.node_limit = limits.nodes;
+        searcher.deadline = budget.map(|budget| start + budget);
+        for depth in 1..=max_depth {
+            let mut pv = vec![];
+            let score = searcher.negamax(depth, 0, -INFINITY, INFINITY, &mut pv);
+            // an unfinished iteration has not looked at every move, so its choice cannot be trusted
+            if searcher.aborted {
+                break;
+            }
+            result = SearchResult {
+                best_move: pv.first().copied().or(result.best_move),
+                score,
+                depth,
+                nodes: searcher.total_nodes(),
+            };
+            on_info(&SearchInfo {
+                depth,
+                score,
+                nodes: searcher.total_nodes(),
+                time: start.elapsed(),
+                hashfull: tt.hashfull(),
+                pv,
+            });
+            // the next iteration takes longer than all before it, so do not start one that cannot finish
+            if budget.is_some_and(|budget| start.elapsed() > budget / 2) {
+                break;
+            }
+        }
+        searcher.flush_nodes();
+        main_finished.store(true, Ordering::Relaxed);
+    });
+    result.nodes = total_nodes.load(Ordering::Relaxed);
+    result
+}
+
+struct Searcher<'a> {
+    board: ArrayBoard,
+    options: &'a SearchOptions,
+    tt: &'a TranspositionTable,
+    // two quiet moves per ply that last caused a beta cutoff there
+    killers: Vec<[Option<Move>; 2]>,
+    // [colour][from][to], raised whenever a quiet move causes a beta cutoff
+    history: Vec<[[i32; 64]; 64]>,
+    nodes: u64,
+    // every thread's nodes, each adding its own in batches
+    total_nodes: &'a AtomicU64,
+    // this thread's nodes already added to total_nodes
+    flushed_nodes: u64,
+    node_limit: Option<u64>,
+    deadline: Option<Instant>,
+    stop: &'a AtomicBool,
+    // set once any limit is hit, after which every node returns straight away
+    aborted: bool,
+}
+
+impl<'a> Searcher<'a> {
+    fn new(
+        array_board: &ArrayBoard,
+        options: &'a SearchOptions,
+        tt: &'a TranspositionTable,
+        stop: &'a AtomicBool,
+        total_nodes: &'a AtomicU64,
+    ) -> Self {
+        Searcher {
+            board: array_board.clone(),
+            options,
+            tt,
+            killers: vec![[None; 2]; MAX_PLY as usize + 1],
+            history: vec![[[0; 64]; 64]; 2],
+            nodes: 0,
+            total_nodes,
+            flushed_nodes: 0,
+            node_limit: None,
+            deadline: None,
+            stop,
+            aborted: false,
+        }
+    }
+    fn negamax(&mut self, depth: u32, ply: u32, mut alpha: i32, beta: i32, pv: &mut Vec<Move>) -> i32 {
+        self.nodes += 1;
+        self.check_limits();
+        if self.aborted {
+            return 0;
+        }
+        if ply > 0 && self.is_draw() {
+            return 0;
+        }
+        // the root is left to search() so it can come up with a move
+        let endgames = self.options.endgames.as_ref().filter(|_endgames| ply > 0);
+        if let Some(value) = endgames.and_then(|endgames| endgames.probe(&self.board)) {
+            return endgame_score(value, ply);
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(ply, alpha, beta);
+        }
+
+        let key = self.board.zobrist_key();
+        let mut hash_move = None;
+        if self.options.transposition_table {
+            if let Some(entry) = self.tt.probe(key, ply) {
+                hash_move = entry.best_move;
+                let usable = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => entry.score >= beta,
+                    Bound::Upper => entry.score <= alpha,
+                };
+                // the root always searches, it has to come up with a move and its line
+                if ply > 0 && entry.depth >= depth && usable {
+                    return entry.score;
+                }
+            }
+        }
+
+        let mut moves = legal_moves_mut(&mut self.board);
+        if moves.is_empty() {
+            // checkmated, sooner is worse, or stalemate
+            return if self.board.in_check.is_some() { -MATE_SCORE + ply as i32 } else { 0 };
+        }
+        if self.options.move_ordering {
+            self.order_moves(&mut moves, hash_move, ply);
+        }
+        let original_alpha = alpha;
+        let mut best_move = None;
+        for mv in moves {
+            let undo = self.board.make_move(mv);
+            let mut child_pv = vec![];
+            let score = -self.negamax(depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            self.board.unmake_move(undo);
+            if self.aborted {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(mv);
+                pv.clear();
+                pv.push(mv);
+                pv.extend(child_pv);
+                if alpha >= beta {
+                    if is_quiet(&mv) {
+                        self.reward_quiet_move(mv, depth, ply);
+                    }
+                    break;
+                }
+            }
+        }
+
+        if self.options.transposition_table {
+            let bound = if alpha >= beta {
+                Bound::Lower
+            } else if alpha > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            self.tt.store(
+                key,
+                ply,
+                TtEntry {
+                    depth,
+                    bound,
+                    score: alpha,
+                    best_move: best_move.as_ref().map(HashMove::new),
+                },
+            );
+        }
+        alpha
+    }
+    // Plays out the captures left at the end of a line so it is not scored halfway through an exchange.
+    // The side to move may also stand pat on the static evaluation unless it is in check.
+    fn quiescence(&mut self, ply: u32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        self.check_limits();
+        if self.aborted {
+            return 0;
+        }
+        if ply >= MAX_PLY {
+            return self.options.params.evaluate(&self.board);
+        }
+        let in_check = self.board.in_check.is_some();
+        if !in_check {
+            let stand_pat = self.options.params.evaluate(&self.board);
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+        }
+
+        let mut moves = legal_moves_mut(&mut self.board);
+        if in_check && moves.is_empty() {
+            return -MATE_SCORE + ply as i32;
+        }
+        // out of check every escape is tried, otherwise only captures and promotions
+        if !in_check {
+            moves.retain(|mv| !is_quiet(mv));
+        }
+        moves.sort_by_key(|mv| Reverse(mvv_lva(mv)));
+        for mv in moves {
+            let undo = self.board.make_move(mv);
+            let score = -self.quiescence(ply + 1, -beta, -alpha);
+            self.board.unmake_move(undo);
+            if self.aborted {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        alpha
+    }
+    // Best first, so the cutoffs come early: the hash move, then captures and promotions
+    // by MVV-LVA, then this ply's killer moves, then the other quiet moves by their history
+    fn order_moves(&self, moves: &mut [Move], hash_move: Option<HashMove>, ply: u32) {
+        let killers = self.killers[ply as usize];
+        let history = &self.history[self.board.turn.index()];
+        moves.sort_by_cached_key(|mv| {
+            let score = if hash_move.is_some_and(|hash_move| hash_move.matches(mv)) {
+                HASH_MOVE_SCORE
+            } else if !is_quiet(mv) {
+                CAPTURE_SCORE + mvv_lva(mv)
+            } else if let Some(slot) = killers.iter().position(|killer| *killer == Some(*mv)) {
+                KILLER_SCORES[slot]
+            } else {
+                history[mv.from.index()][mv.to.index()]
+            };
+            Reverse(score)
+        });
+    }
+    // A quiet move that refuted one of the opponent's moves is likely to refute its others too
+    fn reward_quiet_move(&mut self, mv: Move, depth: u32, ply: u32) {
+        let killers = &mut self.killers[ply as usize];
+        if killers[0] != Some(mv) {
+            killers[1] = killers[0];
+            killers[0] = Some(mv);
+        }
+        let history = &mut self.history[self.board.turn.index()];
+        let score = &mut history[mv.from.index()][mv.to.index()];
+        *score += (depth * depth) as i32;
+        if *score >= HISTORY_LIMIT {
+            for score in history.iter_mut().flatten() {
+                *score /= 2;
+            }
+        }
+    }
+    // A repeated position, the fifty-move rule or too little material to mate ends the line as a draw
+    fn is_draw(&self) -> bool {
+        self.board.repetition_count() >= 2 || self.board.is_fifty_move_draw() || self.board.is_insufficient_material()
+    }
+    fn check_limits(&mut self) {
+        if self.node_limit.is_some_and(|limit| self.total_nodes() >= limit) || self.stop.load(Ordering::Relaxed) {
+            self.aborted = true;
+        }
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL) {
+            self.flush_nodes();
+            if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                self.aborted = true;
+            }
+        }
+    }
+    fn flush_nodes(&mut self) {
+        self.total_nodes.fetch_add(self.nodes - self.flushed_nodes, Ordering::Relaxed);
+        self.flushed_nodes = self.nodes;
+    }
+    fn total_nodes(&self) -> u64 {
+        self.total_nodes.load(Ordering::Relaxed) + self.nodes - self.flushed_nodes
+    }
+}
+
+fn is_quiet(mv: &Move) -> bool {
+    mv.captured.is_none() && mv.promotion.is_none()
+}
+
+// Most valuable victim first, taken by the least valuable attacker
+fn mvv_lva(mv: &Move) -> i32 {
+    let gain = mv.captured.map_or(0, |piece| piece_value(piece.piece_type)) + mv.promotion.map_or(0, piece_value);
+    gain * 10 - piece_value(mv.piece.piece_type) / 100
+}
@@ -0,0 +1,194 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use chess_core::{Move, PieceType};
+
+use crate::search::{MATE_SCORE, MAX_PLY};
+
+pub const DEFAULT_HASH_MB: usize = 16;
+pub const MAX_HASH_MB: usize = 1024;
+// A slot is the position's full key and one packed u64 of data
+const SLOT_BYTES: usize = 16;
+
+// What a stored score says about the position's real score
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    // the score is exact, every move was searched inside the window
+    Exact,
+    // a move reached beta, the real score is at least this
+    Lower,
+    // no move got above alpha, the real score is at most this
+    Upper,
+}
+
+// A move stored as its from and to squares and promotion piece, matched against the moves generated later
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashMove(u16);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtEntry {
+    pub depth: u32,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<HashMove>,
+}
+
+// The key is stored xor the data, so a slot torn by two threads writing it at once no longer matches either key
+// and is simply missed rather than read as the wrong position's result. This is what lets search threads share
+// the table without a lock.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    // packed as move (16 bits) | score (16) | depth (8) | bound (2) | generation (6)
+    data: AtomicU64,
+}
+
+// Search results keyed on Zobrist hashes, so a position reached again through another move order is not searched twice.
+// Each position has one slot, picked by the low bits of its key. A new result replaces the old one there unless
+// the old one was stored during this same search with more depth behind it and is for a different position.
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    // bumped every search so results left from earlier searches give way first
+    generation: AtomicU8,
+}
+
+impl HashMove {
+    pub fn new(mv: &Move) -> Self {
+        let promotion = match mv.promotion {
+            None => 0,
+            Some(PieceType::Knight) => 1,
+            Some(PieceType::Bishop) => 2,
+            Some(PieceType::Rook) => 3,
+            Some(_) => 4,
+        };
+        HashMove(mv.from.index() as u16 | (mv.to.index() as u16) << 6 | promotion << 12)
+    }
+    pub fn matches(&self, mv: &Move) -> bool {
+        *self == HashMove::new(mv)
+    }
+}
+
+impl Bound {
+    fn index(&self) -> u64 {
+        match self {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        }
+    }
+    fn from_index(index: u64) -> Option<Bound> {
+        match index {
+            1 => Some(Bound::Exact),
+            2 => Some(Bound::Lower),
+            3 => Some(Bound::Upper),
+            _ => None,
+        }
+    }
+}
+
+impl TranspositionTable {
+    // The largest power of two number of slots that fits in `megabytes`, at least one
+    pub fn new(megabytes: usize) -> Self {
+        let wanted = megabytes.min(MAX_HASH_MB) * 1024 * 1024 / SLOT_BYTES;
+        let slots = if wanted == 0 { 1 } else { 1 << wanted.ilog2() };
+        TranspositionTable {
+            slots: (0..slots).map(|_| Slot::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.generation.store(0, Ordering::Relaxed);
+    }
+    // Called as each search starts
+    pub fn new_search(&self) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        self.generation.store((generation + 1) % 64, Ordering::Relaxed);
+    }
+    // Mate scores are stored counted from this node rather than the root, so they stay right wherever the position recurs
+    pub fn probe(&self, key: u64, ply: u32) -> Option<TtEntry> {
+        let (stored_key, data) = self.load(self.index(key));
+        if stored_key != key {
+            return None;
+        }
+        let bound = Bound::from_index(data >> 40 & 3)?;
+        let hash_move = (data & 0xffff) as u16;
+        Some(TtEntry {
+            depth: (data >> 32 & 0xff) as u32,
+            bound,
+            score: score_from_tt((data >> 16 & 0xffff) as u16 as i16 as i32, ply),
+            best_move: (hash_move != 0).then_some(HashMove(hash_move)),
+        })
+    }
+    pub fn store(&self, key: u64, ply: u32, entry: TtEntry) {
+        let index = self.index(key);
+        let generation = self.generation.load(Ordering::Relaxed);
+        let (old_key, old_data) = self.load(index);
+        let old_generation = (old_data >> 42) as u8;
+        let old_depth = (old_data >> 32 & 0xff) as u32;
+        if old_key != key && old_generation == generation && old_depth > entry.depth {
+            return;
+        }
+        // a shallower result for the same position keeps the best move it had if it has none of its own
+        let hash_move = match entry.best_move {
+            Some(HashMove(hash_move)) => hash_move as u64,
+            None if old_key == key => old_data & 0xffff,
+            None => 0,
+        };
+        let score = score_to_tt(entry.score, ply) as i16 as u16 as u64;
+        let data = hash_move
+            | score << 16
+            | (entry.depth.min(255) as u64) << 32
+            | entry.bound.index() << 40
+            | (generation as u64) << 42;
+        self.slots[index].key.store(key ^ data, Ordering::Relaxed);
+        self.slots[index].data.store(data, Ordering::Relaxed);
+    }
+    // Roughly how full the table is in thousandths, from a sample of slots, as UCI's hashfull reports it
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.slots.len().min(1000);
+        let generation = self.generation.load(Ordering::Relaxed);
+        let used = (0..sample)
+            .map(|index| self.load(index).1)
+            .filter(|data| *data != 0 && (data >> 42) as u8 == generation)
+            .count();
+        (used * 1000 / sample) as u32
+    }
+    fn index(&self, key: u64) -> usize {
+        key as usize & (self.slots.len() - 1)
+    }
+    // The slot's key and data, the key already xored back
+    fn load(&self, index: usize) -> (u64, u64) {
+        let slot = &self.slots[index];
+        let data = slot.data.load(Ordering::Relaxed);
+        (slot.key.load(Ordering::Relaxed) ^ data, data)
+    }
+}
+
+fn score_to_tt(score: i32, ply: u32) -> i32 {
+    if score >= MATE_SCORE - MAX_PLY as i32 {
+        score + ply as i32
+    } else if score <= -MATE_SCORE + MAX_PLY as i32 {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_tt(score: i32, ply: u32) -> i32 {
+    if score >= MATE_SCORE - MAX_PLY as i32 {
+        score - ply as i32
+    } else if score <= -MATE_SCORE + MAX_PLY as i32 {
+        score + ply as i32
+    } else {
+        score
+    }
+}
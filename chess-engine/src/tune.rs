@@ -0,0 +1,120 @@
+use chess_core::ArrayBoard;
+
+use crate::eval::{features, EvalParams, ParamsError, WEIGHT_COUNT};
+
+// Adam's step size in centipawns and its moment decay rates
+const LEARNING_RATE: f64 = 1.;
+const BETA1: f64 = 0.9;
+const BETA2: f64 = 0.999;
+const EPSILON: f64 = 1e-8;
+
+// A position from a game, reduced to its evaluation features, and how that game ended for white
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingPosition {
+    pub features: Vec<(usize, f64)>,
+    // 1 for a white win, 0.5 for a draw, 0 for a black win
+    pub result: f64,
+}
+
+// Reads one position per line: a FEN and then the game's result as 1-0, 0-1, 1/2-1/2 or 1.0, 0.5, 0.0.
+// The quotes, brackets, c9 opcode and closing semicolon EPD files put around the result are allowed,
+// and blank lines and lines starting with # are skipped.
+pub fn parse_training_set(text: &str) -> Result<Vec<TrainingPosition>, ParamsError> {
+    let mut positions = vec![];
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim().trim_end_matches(';');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| ParamsError(format!("line {}: {message}", line_number + 1));
+        let cleaned = line.replace(['"', '[', ']'], " ");
+        let mut tokens: Vec<&str> = cleaned.split_whitespace().filter(|token| *token != "c9").collect();
+        let result = match tokens.pop() {
+            Some("1-0" | "1.0" | "1") => 1.,
+            Some("0-1" | "0.0" | "0") => 0.,
+            Some("1/2-1/2" | "0.5") => 0.5,
+            _ => return Err(error("no game result at the end".to_string())),
+        };
+        let fen = tokens.join(" ");
+        let array_board = ArrayBoard::from_fen(&fen).map_err(|fen_error| error(format!("{fen_error}")))?;
+        positions.push(TrainingPosition {
+            features: features(&array_board),
+            result,
+        });
+    }
+    Ok(positions)
+}
+
+// Texel's loss: how far, squared and on average, the results are from what the evaluation predicts,
+// the evaluation being turned into an expected score by a logistic curve stretched by `scaling`
+pub fn mean_squared_error(positions: &[TrainingPosition], weights: &[f64], scaling: f64) -> f64 {
+    let total: f64 = positions
+        .iter()
+        .map(|position| (position.result - expected_score(position, weights, scaling)).powi(2))
+        .sum();
+    total / positions.len().max(1) as f64
+}
+
+// The scaling that fits the results best before any weight changes, narrowed down by ternary search
+pub fn fit_scaling(positions: &[TrainingPosition], weights: &[f64]) -> f64 {
+    let (mut low, mut high) = (0.1, 5.);
+    for _ in 0..60 {
+        let a = low + (high - low) / 3.;
+        let b = high - (high - low) / 3.;
+        if mean_squared_error(positions, weights, a) < mean_squared_error(positions, weights, b) {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    (low + high) / 2.
+}
+
+// Gradient descent with Adam on every weight at once, `iterations` times over the whole set,
+// calling `on_iteration` with the iteration and the error so far. Weights no position uses stay as they are.
+pub fn tune(
+    positions: &[TrainingPosition],
+    start: &EvalParams,
+    scaling: f64,
+    iterations: usize,
+    mut on_iteration: impl FnMut(usize, f64),
+) -> EvalParams {
+    let mut weights: Vec<f64> = start.weights().into_iter().map(f64::from).collect();
+    let mut first_moment = vec![0.; WEIGHT_COUNT];
+    let mut second_moment = vec![0.; WEIGHT_COUNT];
+    let slope = scaling * 10_f64.ln() / 400.;
+
+    for iteration in 1..=iterations {
+        let mut gradient = vec![0.; WEIGHT_COUNT];
+        for position in positions {
+            let expected = expected_score(position, &weights, scaling);
+            let error = -2. * (position.result - expected) * expected * (1. - expected) * slope;
+            for (index, coefficient) in &position.features {
+                gradient[*index] += error * coefficient;
+            }
+        }
+        for index in 0..WEIGHT_COUNT {
+            let gradient = gradient[index] / positions.len().max(1) as f64;
+            first_moment[index] = BETA1 * first_moment[index] + (1. - BETA1) * gradient;
+            second_moment[index] = BETA2 * second_moment[index] + (1. - BETA2) * gradient * gradient;
+            let corrected_first = first_moment[index] / (1. - BETA1.powi(iteration as i32));
+            let corrected_second = second_moment[index] / (1. - BETA2.powi(iteration as i32));
+            weights[index] -= LEARNING_RATE * corrected_first / (corrected_second.sqrt() + EPSILON);
+        }
+        on_iteration(iteration, mean_squared_error(positions, &weights, scaling));
+    }
+
+    let mut tuned = start.clone();
+    let rounded: Vec<i32> = weights.iter().map(|weight| weight.round() as i32).collect();
+    tuned.set_weights(&rounded);
+    tuned
+}
+
+fn expected_score(position: &TrainingPosition, weights: &[f64], scaling: f64) -> f64 {
+    let score: f64 = position
+        .features
+        .iter()
+        .map(|(index, coefficient)| weights[*index] * coefficient)
+        .sum();
+    1. / (1. + 10_f64.powf(-scaling * score / 400.))
+}
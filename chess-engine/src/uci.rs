@@ -0,0 +1,220 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chess_core::{ArrayBoard, START_FEN};
+
+use crate::book::load_book;
+use crate::endgame::load_endgames;
+use crate::eval::{load_params, EvalParams};
+use crate::limits::SearchLimits;
+use crate::search::{mate_in, search, SearchInfo, SearchOptions, MAX_THREADS};
+use crate::tt::{TranspositionTable, DEFAULT_HASH_MB, MAX_HASH_MB};
+
+pub const ENGINE_NAME: &str = "chess-rust-project";
+pub const ENGINE_AUTHOR: &str = "Sebastian Wyszynski, Samuel Tinubu, Mark Raskin";
+
+// Speaks the Universal Chess Interface: commands come in a line at a time through handle_command,
+// and replies go to `output`, which the search thread writes its info and bestmove lines to as well
+pub struct UciEngine {
+    board: ArrayBoard,
+    output: Arc<Mutex<dyn Write + Send>>,
+    // kept between searches and shared by all the search threads
+    tt: Arc<TranspositionTable>,
+    options: SearchOptions,
+    stop: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<()>>,
+}
+
+impl UciEngine {
+    pub fn new(output: Arc<Mutex<dyn Write + Send>>) -> Self {
+        UciEngine {
+            board: ArrayBoard::new(),
+            output,
+            tt: Arc::new(TranspositionTable::new(DEFAULT_HASH_MB)),
+            options: SearchOptions::default(),
+            stop: Arc::new(AtomicBool::new(false)),
+            search_thread: None,
+        }
+    }
+    // Handles one line from the GUI, returning false once it says quit
+    pub fn handle_command(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return true;
+        };
+        match command {
+            "uci" => {
+                send(&self.output, &format!("id name {ENGINE_NAME}"));
+                send(&self.output, &format!("id author {ENGINE_AUTHOR}"));
+                send(
+                    &self.output,
+                    &format!("option name Hash type spin default {DEFAULT_HASH_MB} min 1 max {MAX_HASH_MB}"),
+                );
+                send(&self.output, &format!("option name Threads type spin default 1 min 1 max {MAX_THREADS}"));
+                send(&self.output, "option name EvalFile type string default <default>");
+                send(&self.output, "option name BookFile type string default <empty>");
+                send(&self.output, "option name EndgameFile type string default <empty>");
+                send(&self.output, "uciok");
+            }
+            "isready" => send(&self.output, "readyok"),
+            "ucinewgame" => {
+                self.stop_search();
+                self.board = ArrayBoard::new();
+                self.tt.clear();
+            }
+            "position" => self.set_position(args),
+            "go" => self.go(parse_go(args)),
+            "stop" => self.stop_search(),
+            "setoption" => self.set_option(args),
+            "quit" => {
+                self.stop_search();
+                return false;
+            }
+            _ => send(&self.output, &format!("info string unknown command {command}")),
+        }
+        true
+    }
+    // position startpos|fen <FEN> [moves <move>...]
+    fn set_position(&mut self, args: &[&str]) {
+        let moves_at = args.iter().position(|&arg| arg == "moves").unwrap_or(args.len());
+        let fen = match args.first() {
+            Some(&"startpos") => START_FEN.to_string(),
+            Some(&"fen") => args[1..moves_at].join(" "),
+            _ => {
+                send(&self.output, "info string position needs startpos or fen");
+                return;
+            }
+        };
+        let mut board = match ArrayBoard::from_fen(&fen) {
+            Ok(board) => board,
+            Err(error) => {
+                send(&self.output, &format!("info string invalid FEN: {error}"));
+                return;
+            }
+        };
+        for uci in args.iter().skip(moves_at + 1) {
+            match board.parse_uci(uci) {
+                Ok(mv) => {
+                    board.make_move(mv);
+                }
+                Err(error) => {
+                    send(&self.output, &format!("info string {error}"));
+                    return;
+                }
+            }
+        }
+        self.board = board;
+    }
+    fn go(&mut self, limits: SearchLimits) {
+        self.stop_search();
+        self.stop.store(false, Ordering::Relaxed);
+        let board = self.board.clone();
+        let stop = Arc::clone(&self.stop);
+        let output = Arc::clone(&self.output);
+        let tt = Arc::clone(&self.tt);
+        let options = self.options.clone();
+        self.search_thread = Some(thread::spawn(move || {
+            let result = search(&board, &limits, &options, &tt, &stop, |info| send(&output, &info_line(info)));
+            // `go infinite` keeps its answer until the GUI says stop
+            while limits.infinite && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            let best_move = result.best_move.map_or("0000".to_string(), |mv| mv.to_uci());
+            send(&output, &format!("bestmove {best_move}"));
+        }));
+    }
+    fn stop_search(&mut self) {
+        if let Some(search_thread) = self.search_thread.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            let _ = search_thread.join();
+        }
+    }
+    // setoption name <name> [value <value>]
+    fn set_option(&mut self, args: &[&str]) {
+        let value_at = args.iter().position(|&arg| arg == "value").unwrap_or(args.len());
+        let name = args.get(1..value_at).map(|name| name.join(" ")).unwrap_or_default();
+        let value = args.get(value_at + 1..).map(|value| value.join(" ")).unwrap_or_default();
+        match name.to_lowercase().as_str() {
+            "hash" => match value.parse::<usize>() {
+                Ok(megabytes) if (1..=MAX_HASH_MB).contains(&megabytes) => {
+                    self.stop_search();
+                    self.tt = Arc::new(TranspositionTable::new(megabytes));
+                }
+                _ => send(&self.output, &format!("info string Hash must be 1 to {MAX_HASH_MB} MB, not {value}")),
+            },
+            "threads" => match value.parse::<usize>() {
+                Ok(threads) if (1..=MAX_THREADS).contains(&threads) => self.options.threads = threads,
+                _ => send(&self.output, &format!("info string Threads must be 1 to {MAX_THREADS}, not {value}")),
+            },
+            // a parameter file written by the tune binary, <default> goes back to the built-in evaluation
+            "evalfile" if value.is_empty() || value == "<default>" => {
+                self.options.params = Arc::new(EvalParams::default())
+            }
+            "evalfile" => match load_params(&value) {
+                Ok(params) => self.options.params = Arc::new(params),
+                Err(error) => send(&self.output, &format!("info string {error}")),
+            },
+            // a Polyglot .bin book to play from while the game is in it, <empty> plays without one
+            "bookfile" if value.is_empty() || value == "<empty>" => self.options.book = None,
+            "bookfile" => match load_book(&value) {
+                Ok(book) => self.options.book = Some(Arc::new(book)),
+                Err(error) => send(&self.output, &format!("info string {error}")),
+            },
+            // tables written by the endgames binary, <empty> plays without them
+            "endgamefile" if value.is_empty() || value == "<empty>" => self.options.endgames = None,
+            "endgamefile" => match load_endgames(&value) {
+                Ok(endgames) => self.options.endgames = Some(Arc::new(endgames)),
+                Err(error) => send(&self.output, &format!("info string {error}")),
+            },
+            _ => send(&self.output, &format!("info string unknown option {name}")),
+        }
+    }
+}
+
+// Reads the limits of a go command: depth, nodes, movetime, wtime, btime, winc, binc, movestogo, infinite
+pub fn parse_go(args: &[&str]) -> SearchLimits {
+    let mut limits = SearchLimits::default();
+    let number = |i: usize| args.get(i + 1).and_then(|value| value.parse::<u64>().ok());
+    let millis = |i: usize| number(i).map(Duration::from_millis);
+    for (i, &arg) in args.iter().enumerate() {
+        match arg {
+            "depth" => limits.depth = number(i).map(|depth| depth as u32),
+            "nodes" => limits.nodes = number(i),
+            "movetime" => limits.movetime = millis(i),
+            "wtime" => limits.wtime = millis(i),
+            "btime" => limits.btime = millis(i),
+            "winc" => limits.winc = millis(i),
+            "binc" => limits.binc = millis(i),
+            "movestogo" => limits.movestogo = number(i).map(|moves| moves as u32),
+            "infinite" => limits.infinite = true,
+            _ => (),
+        }
+    }
+    limits
+}
+
+pub fn info_line(info: &SearchInfo) -> String {
+    let score = match mate_in(info.score) {
+        Some(moves) => format!("mate {moves}"),
+        None => format!("cp {}", info.score),
+    };
+    let millis = info.time.as_millis().max(1) as u64;
+    let pv: Vec<String> = info.pv.iter().map(|mv| mv.to_uci()).collect();
+    format!(
+        "info depth {} score {score} nodes {} nps {} hashfull {} time {millis} pv {}",
+        info.depth,
+        info.nodes,
+        info.nodes * 1000 / millis,
+        info.hashfull,
+        pv.join(" ")
+    )
+}
+
+fn send(output: &Mutex<dyn Write + Send>, line: &str) {
+    let mut output = output.lock().unwrap();
+    let _ = writeln!(output, "{line}");
+    let _ = output.flush();
+}
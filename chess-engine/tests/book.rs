@@ -0,0 +1,147 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chess_core::{parse_pgn, ArrayBoard, START_FEN};
+use chess_engine::book::{encode_move, load_book, BookBuilder, BookEntry};
+use chess_engine::polyglot::polyglot_key;
+use chess_engine::uci::UciEngine;
+use chess_engine::{search, OpeningBook, SearchLimits, SearchOptions, TranspositionTable};
+
+// A book with these moves and weights from the position
+fn book_for(fen: &str, moves: &[(&str, u16)]) -> OpeningBook {
+    let array_board = ArrayBoard::from_fen(fen).unwrap();
+    let entries = moves
+        .iter()
+        .map(|(uci, weight)| BookEntry {
+            key: polyglot_key(&array_board),
+            mv: encode_move(&array_board.parse_uci(uci).unwrap()),
+            weight: *weight,
+            learn: 0,
+        })
+        .collect();
+    OpeningBook::new(entries)
+}
+
+#[test]
+fn keys_match_the_polyglot_specification() {
+    let positions = [
+        (START_FEN, 0x463b96181691fc9c),
+        ("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", 0x823c9b50fd114196),
+        ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2", 0x0756b94461c50fb0),
+        ("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2", 0x662fafb965db29d4),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", 0x22a48b5a8e47ff78),
+        ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3", 0x652a607ca3f242c1),
+        ("rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4", 0x00fdd303c946bdd9),
+        ("rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3", 0x3c8123ea7b067637),
+        ("rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4", 0x5c3f9b829b279560),
+    ];
+    for (fen, key) in positions {
+        assert_eq!(polyglot_key(&ArrayBoard::from_fen(fen).unwrap()), key, "{fen}");
+    }
+    // the same keys reached by playing the moves, en passant squares and all
+    let mut array_board = ArrayBoard::new();
+    for uci in ["e2e4", "d7d5", "e4e5", "f7f5"] {
+        array_board.make_move(array_board.parse_uci(uci).unwrap());
+    }
+    assert_eq!(polyglot_key(&array_board), 0x22a48b5a8e47ff78);
+}
+
+#[test]
+fn entries_round_trip_through_bytes() {
+    let book = book_for(START_FEN, &[("e2e4", 10), ("d2d4", 7), ("g1f3", 1)]);
+    let bytes = book.to_bytes();
+    assert_eq!(bytes.len(), 48);
+    // big-endian, the key first
+    assert_eq!(bytes[..8], 0x463b96181691fc9c_u64.to_be_bytes());
+    assert_eq!(OpeningBook::from_bytes(&bytes), Ok(book));
+    assert!(OpeningBook::from_bytes(&bytes[..20]).unwrap_err().0.contains("whole number"));
+    assert!(load_book("no-such-book.bin").unwrap_err().0.contains("could not read no-such-book.bin"));
+}
+
+#[test]
+fn moves_are_decoded_against_the_legal_moves() {
+    // castling is stored as the king taking its own rook, e1h1, and comes back as e1g1
+    let fen = "r3k2r/pPpppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1";
+    let array_board = ArrayBoard::from_fen(fen).unwrap();
+    let castle = array_board.parse_uci("e1g1").unwrap();
+    assert_eq!(encode_move(&castle), 7 | 4 << 6);
+    let promotion = array_board.parse_uci("b7a8n").unwrap();
+    assert_eq!(encode_move(&promotion) >> 12, 1);
+    let book = book_for(fen, &[("e1g1", 5), ("b7a8n", 2)]);
+    let moves: Vec<(String, u16)> = book.moves(&array_board).iter().map(|(mv, weight)| (mv.to_uci(), *weight)).collect();
+    assert_eq!(moves, [("e1g1".to_string(), 5), ("b7a8n".to_string(), 2)]);
+    assert!(book.moves(&ArrayBoard::new()).is_empty());
+}
+
+#[test]
+fn picks_by_weight() {
+    let array_board = ArrayBoard::new();
+    let book = book_for(START_FEN, &[("e2e4", 3), ("d2d4", 1), ("a2a3", 0)]);
+    let picks: Vec<String> = (0..8).map(|random| book.pick(&array_board, random).unwrap().to_uci()).collect();
+    assert_eq!(picks, ["e2e4", "e2e4", "e2e4", "d2d4", "e2e4", "e2e4", "e2e4", "d2d4"]);
+    assert!(book_for(START_FEN, &[("a2a3", 0)]).pick(&array_board, 0).is_none());
+}
+
+#[test]
+fn builds_a_book_from_games() {
+    let games = parse_pgn(
+        "[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 1-0\n\n\
+         [Result \"1/2-1/2\"]\n\n1. e4 c5 1/2-1/2\n\n\
+         [Result \"0-1\"]\n\n1. d4 d5 0-1\n",
+    )
+    .unwrap();
+    let mut builder = BookBuilder::new(2);
+    for game in &games {
+        builder.add_game(game);
+    }
+    let book = builder.build(1);
+    let moves: Vec<(String, u16)> = book.moves(&ArrayBoard::new()).iter().map(|(mv, weight)| (mv.to_uci(), *weight)).collect();
+    // 2 points for the win and 1 for the draw, d4 only lost and is left out
+    assert_eq!(moves, [("e2e4".to_string(), 3)]);
+    // e4, c5 and d5, e5 only lost and Nf3 is the third ply, past the two asked for
+    assert_eq!(book.len(), 3);
+    // only e4 was played in more than one game
+    assert_eq!(builder.build(2).len(), 1);
+}
+
+#[test]
+fn search_plays_from_the_book() {
+    let options = SearchOptions {
+        book: Some(Arc::new(book_for(START_FEN, &[("b1a3", 1)]))),
+        ..Default::default()
+    };
+    let tt = TranspositionTable::new(1);
+    let result = search(&ArrayBoard::new(), &SearchLimits::depth(4), &options, &tt, &AtomicBool::new(false), |_info| ());
+    assert_eq!(result.best_move.unwrap().to_uci(), "b1a3");
+    assert_eq!(result.nodes, 0);
+    // out of book it searches as usual
+    let array_board = ArrayBoard::from_fen("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1").unwrap();
+    let result = search(&array_board, &SearchLimits::depth(2), &options, &tt, &AtomicBool::new(false), |_info| ());
+    assert_eq!(result.best_move.unwrap().to_uci(), "d1d5");
+}
+
+#[test]
+fn uci_book_file_option() {
+    let path = std::env::temp_dir().join(format!("chess-engine-test-{}.bin", std::process::id()));
+    std::fs::write(&path, book_for(START_FEN, &[("h2h3", 1)]).to_bytes()).unwrap();
+    let output = Arc::new(Mutex::new(vec![]));
+    let mut engine = UciEngine::new(output.clone());
+    engine.handle_command("setoption name BookFile value no-such-book.bin");
+    engine.handle_command(&format!("setoption name BookFile value {}", path.display()));
+    engine.handle_command("position startpos");
+    engine.handle_command("go depth 3");
+    std::fs::remove_file(&path).unwrap();
+    let mut text = String::new();
+    for _ in 0..1000 {
+        text = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        if text.contains("bestmove") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with("info string could not read no-such-book.bin"), "{lines:?}");
+    assert_eq!(lines.last(), Some(&"bestmove h2h3"));
+}
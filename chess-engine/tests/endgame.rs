@@ -0,0 +1,134 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use chess_core::ArrayBoard;
+use chess_engine::endgame::{load_endgames, EndgamePosition, EndgameValue, Ending};
+use chess_engine::search::{mate_in, MATE_SCORE};
+use chess_engine::uci::UciEngine;
+use chess_engine::{search, EndgameTables, SearchLimits, SearchOptions, TranspositionTable};
+
+// KPK, with the KQK and KRK tables it promotes into, generated once for every test.
+// KBNK takes too long outside a release build and gets its own ignored test.
+fn tables() -> &'static EndgameTables {
+    static TABLES: OnceLock<EndgameTables> = OnceLock::new();
+    TABLES.get_or_init(|| EndgameTables::generate(&[Ending::Kpk], |_ending, _time| ()))
+}
+
+fn probe(fen: &str) -> Option<EndgameValue> {
+    tables().probe(&ArrayBoard::from_fen(fen).unwrap())
+}
+
+#[test]
+fn longest_mates_are_the_known_ones() {
+    assert_eq!(tables().endings(), [Ending::Kqk, Ending::Krk, Ending::Kpk]);
+    assert_eq!(tables().longest_mate(Ending::Kqk), Some(10));
+    assert_eq!(tables().longest_mate(Ending::Krk), Some(16));
+    assert_eq!(tables().longest_mate(Ending::Kpk), Some(28));
+    assert_eq!(tables().longest_mate(Ending::Kbnk), None);
+}
+
+// Too slow for a debug build, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn bishop_and_knight_mate() {
+    let tables = EndgameTables::generate(&[Ending::Kbnk], |_ending, _time| ());
+    assert_eq!(tables.longest_mate(Ending::Kbnk), Some(33));
+    // Be5 mates, the knight covering g8
+    let array_board = ArrayBoard::from_fen("7k/4N3/6K1/8/5B2/8/8/8 w - - 0 1").unwrap();
+    assert_eq!(tables.probe(&array_board), Some(EndgameValue::Win(1)));
+    assert_eq!(tables.best_move(&array_board).map(|(mv, _value)| mv.to_uci()), Some("f4e5".to_string()));
+    // with Black to move the same position is stalemate
+    assert_eq!(tables.probe(&ArrayBoard::from_fen("7k/4N3/6K1/8/5B2/8/8/8 b - - 0 1").unwrap()), Some(EndgameValue::Draw));
+}
+
+#[test]
+fn king_and_pawn_wins_and_draws() {
+    // the defending king in front of the pawn holds with the opposition, and loses without it
+    assert_eq!(probe("8/4k3/8/4K3/4P3/8/8/8 w - - 0 1"), Some(EndgameValue::Draw));
+    assert!(matches!(probe("8/4k3/8/4K3/4P3/8/8/8 b - - 0 1"), Some(EndgameValue::Loss(_))));
+    // a rook's pawn is a draw once the king is in the corner
+    assert_eq!(probe("k7/8/K7/P7/8/8/8/8 w - - 0 1"), Some(EndgameValue::Draw));
+    // the pawn runs home and the king cannot catch it
+    assert!(matches!(probe("7k/8/8/8/P7/8/8/K7 w - - 0 1"), Some(EndgameValue::Win(_))));
+    // nothing for other material, or while castling rights are left
+    assert_eq!(probe("4k3/8/4K3/4P3/4P3/8/8/8 w - - 0 1"), None);
+    assert_eq!(probe("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1"), None);
+}
+
+#[test]
+fn black_with_the_pieces_is_turned_around() {
+    let (ending, position) = EndgamePosition::from_board(&ArrayBoard::from_fen("8/8/8/4p3/4k3/8/4K3/8 w - - 0 1").unwrap()).unwrap();
+    assert_eq!(ending, Ending::Kpk);
+    assert!(!position.white_to_move);
+    assert_eq!(position.pieces[0].to_string(), "e4");
+    // the same positions as in king_and_pawn_wins_and_draws with the colours swapped
+    assert_eq!(probe("8/8/8/4p3/4k3/8/4K3/8 b - - 0 1"), probe("8/4k3/8/4K3/4P3/8/8/8 w - - 0 1"));
+    assert_eq!(probe("8/8/8/4p3/4k3/8/4K3/8 w - - 0 1"), probe("8/4k3/8/4K3/4P3/8/8/8 b - - 0 1"));
+    assert_eq!(probe("8/8/8/8/8/8/8/r3k2K w - - 0 1"), probe("R3K2k/8/8/8/8/8/8/8 b - - 0 1"));
+}
+
+#[test]
+fn best_moves() {
+    // Qh8 is the only mate
+    let array_board = ArrayBoard::from_fen("k7/8/1K6/8/8/8/7Q/8 w - - 0 1").unwrap();
+    let (mv, value) = tables().best_move(&array_board).unwrap();
+    assert_eq!((mv.to_uci().as_str(), value), ("h2h8", EndgameValue::Win(1)));
+    assert_eq!(tables().probe(&array_board), Some(EndgameValue::Win(1)));
+    // after 1. Kd5 only taking the opposition with Kd7 holds the draw, every other move loses
+    let (mv, value) = tables().best_move(&ArrayBoard::from_fen("8/4k3/8/3K4/4P3/8/8/8 b - - 0 1").unwrap()).unwrap();
+    assert_eq!((mv.to_uci().as_str(), value), ("e7d7", EndgameValue::Draw));
+}
+
+#[test]
+fn tables_round_trip_through_bytes() {
+    let bytes = tables().to_bytes();
+    assert_eq!(&bytes[..8], b"CHESSEGT");
+    assert_eq!(EndgameTables::from_bytes(&bytes).as_ref(), Ok(tables()));
+    assert_eq!(EndgameTables::from_bytes(b"not a table").unwrap_err().0, "not an endgame table file");
+    assert!(EndgameTables::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err().0.contains("wrong size"));
+    assert!(load_endgames("no-such-tables.egt").unwrap_err().0.contains("could not read no-such-tables.egt"));
+}
+
+#[test]
+fn search_plays_from_the_tables() {
+    let options = SearchOptions {
+        endgames: Some(Arc::new(tables().clone())),
+        ..Default::default()
+    };
+    let tt = TranspositionTable::new(1);
+    let array_board = ArrayBoard::from_fen("k7/8/1K6/8/8/8/7Q/8 w - - 0 1").unwrap();
+    let result = search(&array_board, &SearchLimits::depth(4), &options, &tt, &AtomicBool::new(false), |_info| ());
+    assert_eq!(result.best_move.unwrap().to_uci(), "h2h8");
+    assert_eq!((result.score, result.nodes), (MATE_SCORE - 1, 0));
+    // taking the knight reaches KRK, which the tables score as a mate far past the depth searched
+    let array_board = ArrayBoard::from_fen("4k3/8/8/n7/8/8/8/R3K3 w - - 0 1").unwrap();
+    let result = search(&array_board, &SearchLimits::depth(2), &options, &tt, &AtomicBool::new(false), |_info| ());
+    assert_eq!(result.best_move.unwrap().to_uci(), "a1a5");
+    assert!(mate_in(result.score).is_some_and(|moves| moves > 1), "{}", result.score);
+}
+
+#[test]
+fn uci_endgame_file_option() {
+    let path = std::env::temp_dir().join(format!("chess-engine-test-{}.egt", std::process::id()));
+    std::fs::write(&path, tables().to_bytes()).unwrap();
+    let output = Arc::new(Mutex::new(vec![]));
+    let mut engine = UciEngine::new(output.clone());
+    engine.handle_command("setoption name EndgameFile value no-such-tables.egt");
+    engine.handle_command(&format!("setoption name EndgameFile value {}", path.display()));
+    std::fs::remove_file(&path).unwrap();
+    engine.handle_command("position fen 8/4k3/8/3K4/4P3/8/8/8 b - - 0 1");
+    engine.handle_command("go depth 1");
+    let mut text = String::new();
+    for _ in 0..1000 {
+        text = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        if text.contains("bestmove") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with("info string could not read no-such-tables.egt"), "{lines:?}");
+    assert_eq!(lines.last(), Some(&"bestmove e7d7"));
+}
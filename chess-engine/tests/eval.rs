@@ -0,0 +1,110 @@
+use chess_core::{ArrayBoard, PieceType};
+use chess_engine::eval::features;
+use chess_engine::tune::{fit_scaling, mean_squared_error, parse_training_set, tune};
+use chess_engine::EvalParams;
+
+const POSITIONS: [&str; 4] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R b KQ - 0 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "6k1/5ppp/8/3P4/8/8/1P3PPP/6K1 b - - 0 1",
+];
+
+#[test]
+fn evaluation_is_symmetric() {
+    let params = EvalParams::default();
+    assert_eq!(params.evaluate(&ArrayBoard::new()), 0);
+    // the same position with the colours swapped scores the same for the side to move
+    let white = ArrayBoard::from_fen("4k3/pp6/8/3N4/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+    let black = ArrayBoard::from_fen("r5k1/5ppp/8/8/3n4/8/PP6/4K3 b - - 0 1").unwrap();
+    assert_eq!(params.evaluate(&white), params.evaluate(&black));
+    assert!(params.evaluate(&white) > 0);
+}
+
+#[test]
+fn features_add_up_to_the_evaluation() {
+    let params = EvalParams::default();
+    let weights = params.weights();
+    for fen in POSITIONS {
+        let array_board = ArrayBoard::from_fen(fen).unwrap();
+        let white_score: f64 = features(&array_board)
+            .iter()
+            .map(|(index, coefficient)| weights[*index] as f64 * coefficient)
+            .sum();
+        let score = if array_board.turn.is_white() {
+            white_score
+        } else {
+            -white_score
+        };
+        assert!(
+            (score - params.evaluate(&array_board) as f64).abs() <= 1.,
+            "{fen}: {score} from features"
+        );
+    }
+}
+
+#[test]
+fn parameter_text_round_trip() {
+    let mut params = EvalParams::default();
+    params.piece_values[PieceType::Knight.index()] = 333;
+    params.endgame_tables[PieceType::Rook.index()][12] = -7;
+    params.passed_pawn[6] = 123;
+    assert_eq!(EvalParams::from_text(&params.to_text()), Ok(params));
+
+    // groups left out keep their defaults
+    let partial = EvalParams::from_text("# only the king safety\nking_shield 20\nking_attack 7 # per attack\n").unwrap();
+    assert_eq!(partial.king_shield, 20);
+    assert_eq!(partial.king_attack, 7);
+    assert_eq!(partial.piece_values, EvalParams::default().piece_values);
+}
+
+#[test]
+fn bad_parameter_files() {
+    assert!(EvalParams::from_text("king_safety 10").unwrap_err().0.contains("unknown parameter 'king_safety'"));
+    assert!(EvalParams::from_text("mobility 1 2 3").unwrap_err().0.contains("needs 6 numbers"));
+    assert!(EvalParams::from_text("\nking_shield ten").unwrap_err().0.contains("line 2"));
+}
+
+#[test]
+fn training_set_formats() {
+    let text = "\
+# plain FEN and result
+8/8/8/8/8/4K3/8/4k2Q b - - 0 1 1-0
+4k3/8/8/8/8/8/8/q5K1 w - - c9 \"0-1\";
+4k3/8/8/8/8/8/8/4K3 w - - [0.5]
+";
+    let positions = parse_training_set(text).unwrap();
+    let results: Vec<f64> = positions.iter().map(|position| position.result).collect();
+    assert_eq!(results, [1., 0., 0.5]);
+    assert!(parse_training_set("4k3/8/8/8/8/8/8/4K3 w - -")
+        .unwrap_err()
+        .0
+        .contains("line 1"));
+}
+
+#[test]
+fn tuning_lowers_the_error() {
+    // white keeps winning with an extra knight, so the knight is worth more than it is given
+    let mut text = String::new();
+    for fen in [
+        "4k3/pppp4/8/8/8/2N5/PPPP4/4K3 w - - 0 1",
+        "4k3/pppp4/8/8/8/5N2/PPPP4/4K3 b - - 0 1",
+        "4k3/pp6/8/8/3N4/8/PP6/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/4N3/4K3 b - - 0 1",
+    ] {
+        text.push_str(&format!("{fen} 1-0\n"));
+    }
+    text.push_str("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1 1/2-1/2\n");
+    let positions = parse_training_set(&text).unwrap();
+    let start = EvalParams::default();
+    let start_weights: Vec<f64> = start.weights().into_iter().map(f64::from).collect();
+    let scaling = fit_scaling(&positions, &start_weights);
+    let tuned = tune(&positions, &start, scaling, 200, |_iteration, _error| ());
+    let tuned_weights: Vec<f64> = tuned.weights().into_iter().map(f64::from).collect();
+    assert!(mean_squared_error(&positions, &tuned_weights, scaling) < mean_squared_error(&positions, &start_weights, scaling));
+    let knight = PieceType::Knight.index();
+    assert!(tuned.piece_values[knight] > start.piece_values[knight]);
+    // nothing here has a queen on the board, so nothing moves her value
+    let queen = PieceType::Queen.index();
+    assert_eq!(tuned.piece_values[queen], start.piece_values[queen]);
+}
@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chess_core::ArrayBoard;
+use chess_engine::search::mate_in;
+use chess_engine::{search, SearchLimits, SearchOptions, TranspositionTable};
+
+fn best_move(fen: &str, depth: u32) -> String {
+    let array_board = ArrayBoard::from_fen(fen).unwrap();
+    let result = search(&array_board, &SearchLimits::depth(depth), &SearchOptions::default(), &TranspositionTable::new(1), &AtomicBool::new(false), |_info| ());
+    result.best_move.unwrap().to_uci()
+}
+
+fn with_threads(threads: usize) -> SearchOptions {
+    SearchOptions {
+        threads,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn takes_a_hanging_queen() {
+    assert_eq!(best_move("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1", 1), "d1d5");
+}
+
+#[test]
+fn quiescence_sees_the_recapture() {
+    // at depth 1 Qxd5 wins a pawn, until exd5 takes the queen back
+    assert_ne!(best_move("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", 1), "d1d5");
+}
+
+#[test]
+fn finds_mate_in_two() {
+    // either rook cuts the king off on the seventh rank and the other mates on the eighth
+    let array_board = ArrayBoard::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1").unwrap();
+    let result = search(&array_board, &SearchLimits::depth(4), &SearchOptions::default(), &TranspositionTable::new(1), &AtomicBool::new(false), |_info| ());
+    assert_eq!(mate_in(result.score), Some(2));
+    assert!(["b1b7", "a2a7"].contains(&result.best_move.unwrap().to_uci().as_str()));
+}
+
+#[test]
+fn one_thread_is_deterministic() {
+    let array_board = ArrayBoard::from_fen("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQ - 0 8").unwrap();
+    let run = || {
+        let mut infos = vec![];
+        let result = search(&array_board, &SearchLimits::depth(4), &with_threads(1), &TranspositionTable::new(1), &AtomicBool::new(false), |info| {
+            infos.push((info.depth, info.score, info.nodes, info.pv.clone()))
+        });
+        (result, infos)
+    };
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn more_threads_find_the_same_mate() {
+    let array_board = ArrayBoard::from_fen("7k/8/8/8/8/8/R7/1R4K1 w - - 0 1").unwrap();
+    let result = search(&array_board, &SearchLimits::depth(4), &with_threads(4), &TranspositionTable::new(1), &AtomicBool::new(false), |_info| ());
+    assert_eq!(mate_in(result.score), Some(2));
+}
+
+#[test]
+fn stop_ends_every_thread() {
+    let array_board = ArrayBoard::new();
+    let stop = AtomicBool::new(false);
+    let start = Instant::now();
+    let result = thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(100));
+            stop.store(true, Ordering::Relaxed);
+        });
+        search(&array_board, &SearchLimits::default(), &with_threads(4), &TranspositionTable::new(1), &stop, |_info| ())
+    });
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(array_board.parse_uci(&result.best_move.unwrap().to_uci()).is_ok());
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::AtomicBool;
+
+use chess_core::ArrayBoard;
+use chess_engine::search::MATE_SCORE;
+use chess_engine::tt::{Bound, HashMove, TtEntry};
+use chess_engine::{search, SearchLimits, SearchOptions, TranspositionTable};
+
+fn entry(depth: u32, score: i32) -> TtEntry {
+    TtEntry {
+        depth,
+        bound: Bound::Exact,
+        score,
+        best_move: None,
+    }
+}
+
+#[test]
+fn size_is_a_power_of_two_within_the_megabytes() {
+    let tt = TranspositionTable::new(1);
+    assert_eq!(tt.len(), 1 << 16);
+    assert_eq!(TranspositionTable::new(3).len(), 1 << 17);
+}
+
+#[test]
+fn stores_and_probes() {
+    let array_board = ArrayBoard::new();
+    let mv = array_board.parse_uci("e2e4").unwrap();
+    let tt = TranspositionTable::new(1);
+    tt.new_search();
+    let key = array_board.zobrist_key();
+    assert_eq!(tt.probe(key, 0), None);
+    let stored = TtEntry {
+        depth: 7,
+        bound: Bound::Lower,
+        score: -35,
+        best_move: Some(HashMove::new(&mv)),
+    };
+    tt.store(key, 0, stored);
+    assert_eq!(tt.probe(key, 0), Some(stored));
+    assert!(tt.probe(key, 0).unwrap().best_move.unwrap().matches(&mv));
+    // another key sharing the slot is not mistaken for this one
+    assert_eq!(tt.probe(key ^ (1 << 40), 0), None);
+    tt.clear();
+    assert_eq!(tt.probe(key, 0), None);
+}
+
+#[test]
+fn mate_scores_are_stored_from_the_node() {
+    let tt = TranspositionTable::new(1);
+    tt.new_search();
+    // mate three plies below a node at ply 4 is mate in seven from the root, but in five from a node at ply 2
+    tt.store(42, 4, entry(3, MATE_SCORE - 7));
+    assert_eq!(tt.probe(42, 2).unwrap().score, MATE_SCORE - 5);
+}
+
+#[test]
+fn deeper_results_of_this_search_are_kept() {
+    let tt = TranspositionTable::new(1);
+    let slots = tt.len() as u64;
+    tt.new_search();
+    tt.store(1, 0, entry(6, 10));
+    // a shallower result for another position in the same slot gives way
+    tt.store(1 + slots, 0, entry(2, 20));
+    assert_eq!(tt.probe(1, 0).unwrap().score, 10);
+    assert_eq!(tt.probe(1 + slots, 0), None);
+    // but the same position is always updated
+    tt.store(1, 0, entry(2, 30));
+    assert_eq!(tt.probe(1, 0).unwrap().score, 30);
+    // and anything left from an earlier search is replaced
+    tt.store(1, 0, entry(9, 40));
+    tt.new_search();
+    tt.store(1 + slots, 0, entry(1, 50));
+    assert_eq!(tt.probe(1 + slots, 0).unwrap().score, 50);
+}
+
+#[test]
+fn move_ordering_searches_fewer_nodes() {
+    let array_board = ArrayBoard::from_fen("r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/PP3PPP/R2QKB1R w KQ - 0 8").unwrap();
+    let run = |transposition_table, move_ordering| {
+        let options = SearchOptions {
+            transposition_table,
+            move_ordering,
+            ..Default::default()
+        };
+        let tt = TranspositionTable::new(1);
+        search(&array_board, &SearchLimits::depth(3), &options, &tt, &AtomicBool::new(false), |_info| ())
+    };
+    let plain = run(false, false);
+    let ordered = run(true, true);
+    assert_eq!(ordered.score, plain.score);
+    assert!(ordered.nodes * 2 < plain.nodes, "{} nodes ordered, {} plain", ordered.nodes, plain.nodes);
+}
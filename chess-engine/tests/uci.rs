@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chess_engine::uci::{parse_go, UciEngine};
+
+// An engine writing into a buffer the test can read back
+fn engine() -> (UciEngine, Arc<Mutex<Vec<u8>>>) {
+    let output = Arc::new(Mutex::new(vec![]));
+    (UciEngine::new(output.clone()), output)
+}
+
+fn lines(output: &Mutex<Vec<u8>>) -> Vec<String> {
+    String::from_utf8(output.lock().unwrap().clone())
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+// Waits for the search thread to answer
+fn best_move(output: &Mutex<Vec<u8>>) -> String {
+    for _ in 0..1000 {
+        if let Some(line) = lines(output).iter().find(|line| line.starts_with("bestmove")) {
+            return line["bestmove ".len()..].to_string();
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("no bestmove in {:?}", lines(output));
+}
+
+#[test]
+fn handshake() {
+    let (mut engine, output) = engine();
+    assert!(engine.handle_command("uci"));
+    assert!(engine.handle_command("isready"));
+    let lines = lines(&output);
+    assert!(lines[0].starts_with("id name "));
+    assert!(lines.contains(&"uciok".to_string()));
+    assert_eq!(lines.last().unwrap(), "readyok");
+    assert!(!engine.handle_command("quit"));
+}
+
+#[test]
+fn finds_mate_in_one_after_moves() {
+    let (mut engine, output) = engine();
+    engine.handle_command("position startpos moves f2f3 e7e5 g2g4");
+    engine.handle_command("go depth 2");
+    assert_eq!(best_move(&output), "d8h4");
+    assert!(lines(&output).iter().any(|line| line.starts_with("info depth 2 score mate 1 ")));
+}
+
+#[test]
+fn position_from_fen() {
+    let (mut engine, output) = engine();
+    // the only legal move is to take the checking queen
+    engine.handle_command("position fen 7k/8/8/8/8/8/6q1/7K w - - 0 1");
+    engine.handle_command("go nodes 1000");
+    assert_eq!(best_move(&output), "h1g2");
+}
+
+#[test]
+fn infinite_search_waits_for_stop() {
+    let (mut engine, output) = engine();
+    engine.handle_command("position startpos");
+    engine.handle_command("go infinite");
+    thread::sleep(Duration::from_millis(50));
+    assert!(!lines(&output).iter().any(|line| line.starts_with("bestmove")));
+    engine.handle_command("stop");
+    assert!(!best_move(&output).is_empty());
+}
+
+#[test]
+fn hash_option() {
+    let (mut engine, output) = engine();
+    engine.handle_command("uci");
+    assert!(lines(&output).iter().any(|line| line.starts_with("option name Hash type spin")));
+    engine.handle_command("setoption name Hash value 4");
+    engine.handle_command("setoption name Hash value 0");
+    engine.handle_command("setoption name Ponder value true");
+    let lines = lines(&output);
+    let replies: Vec<&String> = lines.iter().filter(|line| line.starts_with("info string")).collect();
+    assert_eq!(replies.len(), 2, "{replies:?}");
+    assert!(replies[0].contains("Hash must be"));
+    assert!(replies[1].contains("unknown option Ponder"));
+}
+
+#[test]
+fn threads_option() {
+    let (mut engine, output) = engine();
+    engine.handle_command("uci");
+    assert!(lines(&output).iter().any(|line| line.starts_with("option name Threads type spin default 1")));
+    engine.handle_command("setoption name Threads value 0");
+    assert!(lines(&output).last().unwrap().contains("Threads must be"));
+    engine.handle_command("setoption name Threads value 4");
+    engine.handle_command("position startpos moves f2f3 e7e5 g2g4");
+    engine.handle_command("go infinite");
+    thread::sleep(Duration::from_millis(100));
+    engine.handle_command("stop");
+    assert_eq!(best_move(&output), "d8h4");
+}
+
+#[test]
+fn go_arguments() {
+    let limits = parse_go(&["wtime", "60000", "btime", "55000", "winc", "1000", "binc", "1000", "movestogo", "20"]);
+    assert_eq!(limits.wtime, Some(Duration::from_millis(60000)));
+    assert_eq!(limits.binc, Some(Duration::from_millis(1000)));
+    assert_eq!(limits.movestogo, Some(20));
+    assert_eq!(parse_go(&["depth", "7"]).depth, Some(7));
+    assert!(parse_go(&["infinite"]).infinite);
+}
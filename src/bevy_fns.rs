@@ -1,17 +1,34 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 
-pub mod array;
-use array::structs::{Piece, PieceColour, PieceType, Position, CurrentSelectedPiece, Redtile};
+pub mod components;
+pub mod computer;
+pub mod endgame_hint;
+pub mod game_record;
+pub mod history;
+pub mod replay;
+use chess_core::{DrawReason, GameOutcome, Move, Piece, PieceColour, PieceType, Square};
+use components::{ComputerPlayer, GameBoard, GameRecord, GamePiece, MoveHistory, Position, CurrentSelectedPiece, Redtile, PromotionChoice, ResultBanner};
 
-use self::array::{valid_tiles, ArrayBoard, in_check_valid_tiles, can_take_king};
+use chess_core::{legal_moves, game_outcome};
+use chess_core::movegen::castling_rook_tiles;
 
 const TILE_SIZE: f32 = 80.0;
 const BOARD_SIZE: usize = 8;
 
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Playing,
+    GameOver,
+    // stepping through games loaded from a PGN file
+    Replay,
+}
+
 pub fn setup_board(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
     asset_server: Res<AssetServer>,
+    array_board: Res<GameBoard>,
 ) {
     let window = window_query.get_single().unwrap();
     let horiz_displacement = window.width() / 2. - TILE_SIZE * 3.5;
@@ -41,255 +58,50 @@ pub fn setup_board(
         }
     }
     // Put the pieces on the board
-    // First pawns
-    for col in 0..BOARD_SIZE {
-        //black pawns
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    col as f32 * TILE_SIZE + horiz_displacement,
-                    6. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Bpawn.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::Black,
-                piece_type: PieceType::Pawn,
-            },
-            Position {
-                x: col as f32,
-                y: 6.,
-            },
-        ));
-        //white pawns
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    col as f32 * TILE_SIZE + horiz_displacement,
-                    1. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Wpawn.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::White,
-                piece_type: PieceType::Pawn,
-            },
-            Position {
-                x: col as f32,
-                y: 1.,
-            },
-        ));
-    }
-
-    //kings
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(
-                4. * TILE_SIZE + horiz_displacement,
-                0. * TILE_SIZE + vert_displacement,
-                0.0,
-            )
-            .with_scale(Vec3::new(0.25, 0.25, 1.)),
-            texture: asset_server.load("sprites/Wking.png"),
-            ..default()
-        },
-        Piece {
-            colour: PieceColour::White,
-            piece_type: PieceType::King,
-        },
-        Position { x: 4., y: 0. },
-    ));
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(
-                4. * TILE_SIZE + horiz_displacement,
-                7. * TILE_SIZE + vert_displacement,
-                0.0,
-            )
-            .with_scale(Vec3::new(0.25, 0.25, 1.)),
-            texture: asset_server.load("sprites/Bking.png"),
-            ..default()
-        },
-        Piece {
-            colour: PieceColour::Black,
-            piece_type: PieceType::King,
-        },
-        Position { x: 4., y: 7. },
-    ));
-
-    //queens
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(
-                3. * TILE_SIZE + horiz_displacement,
-                0. * TILE_SIZE + vert_displacement,
-                0.0,
-            )
-            .with_scale(Vec3::new(0.25, 0.25, 1.)),
-            texture: asset_server.load("sprites/Wqueen.png"),
-            ..default()
-        },
-        Piece {
-            colour: PieceColour::White,
-            piece_type: PieceType::Queen,
-        },
-        Position { x: 3., y: 0. },
-    ));
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(
-                3. * TILE_SIZE + horiz_displacement,
-                7. * TILE_SIZE + vert_displacement,
-                0.0,
-            )
-            .with_scale(Vec3::new(0.25, 0.25, 1.)),
-            texture: asset_server.load("sprites/Bqueen.png"),
-            ..default()
-        },
-        Piece {
-            colour: PieceColour::Black,
-            piece_type: PieceType::Queen,
-        },
-        Position { x: 3., y: 7. },
-    ));
-
-    //Bishops
-    for col in 0..2 {
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 3. + 2.) * TILE_SIZE + horiz_displacement,
-                    7. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Bbishop.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::Black,
-                piece_type: PieceType::Bishop,
-            },
-            Position {
-                x: (col as f32 * 3. + 2.),
-                y: 7.,
-            },
-        ));
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 3. + 2.) * TILE_SIZE + horiz_displacement,
-                    0. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Wbishop.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::White,
-                piece_type: PieceType::Bishop,
-            },
-            Position {
-                x: (col as f32 * 3. + 2.),
-                y: 0.,
-            },
-        ));
-    }
+    spawn_pieces(&mut commands, &asset_server, &array_board, horiz_displacement, vert_displacement);
+}
 
-    // Knights
-    for col in 0..2 {
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 5. + 1.) * TILE_SIZE + horiz_displacement,
-                    7. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Bhorse.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::Black,
-                piece_type: PieceType::Knight,
-            },
-            Position {
-                x: (col as f32 * 5. + 1.),
-                y: 7.,
-            },
-        ));
+// One sprite per piece on the board, so any position (start, FEN, saved game) renders the same way
+pub fn spawn_pieces(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    array_board: &GameBoard,
+    horiz_displacement: f32,
+    vert_displacement: f32,
+) {
+    for (square, piece) in array_board.pieces() {
         commands.spawn((
             SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 5. + 1.) * TILE_SIZE + horiz_displacement,
-                    0. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
+                transform: Transform::from_translation(tile_translation(
+                    square,
+                    horiz_displacement,
+                    vert_displacement,
+                    0.,
+                ))
                 .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Whorse.png"),
+                texture: asset_server.load(sprite_path(&piece)),
                 ..default()
             },
-            Piece {
-                colour: PieceColour::White,
-                piece_type: PieceType::Knight,
-            },
-            Position {
-                x: (col as f32 * 5. + 1.),
-                y: 0.,
-            },
+            GamePiece(piece),
+            Position(square),
         ));
     }
+}
 
-    // Rooks
-    for col in 0..2 {
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 7.) * TILE_SIZE + horiz_displacement,
-                    7. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Brook.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::Black,
-                piece_type: PieceType::Rook,
-            },
-            Position {
-                x: (col as f32 * 7.),
-                y: 7.,
-            },
-        ));
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(
-                    (col as f32 * 7.) * TILE_SIZE + horiz_displacement,
-                    0. * TILE_SIZE + vert_displacement,
-                    0.0,
-                )
-                .with_scale(Vec3::new(0.25, 0.25, 1.)),
-                texture: asset_server.load("sprites/Wrook.png"),
-                ..default()
-            },
-            Piece {
-                colour: PieceColour::White,
-                piece_type: PieceType::Rook,
-            },
-            Position {
-                x: (col as f32 * 7.),
-                y: 0.,
-            },
-        ));
+// Throws every piece sprite away and spawns them again from the board, for jumps that are not one move
+pub fn redraw_pieces(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    array_board: &GameBoard,
+    pieces: &Query<Entity, With<GamePiece>>,
+    window: &Window,
+) {
+    for entity in pieces.iter() {
+        commands.entity(entity).despawn();
     }
+    let horiz_displacement = window.width() / 2. - TILE_SIZE * 3.5;
+    let vert_displacement = window.height() / 2. - TILE_SIZE * 3.5;
+    spawn_pieces(commands, asset_server, array_board, horiz_displacement, vert_displacement);
 }
 
 pub fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
@@ -300,25 +112,93 @@ pub fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<Pr
     });
 }
 
+pub fn check_game_over(array_board: Res<GameBoard>, mut next_state: ResMut<NextState<GameState>>) {
+    if game_outcome(&array_board) != GameOutcome::InProgress {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+pub fn spawn_result_banner(
+    mut commands: Commands,
+    array_board: Res<GameBoard>,
+    asset_server: Res<AssetServer>,
+) {
+    let message = match game_outcome(&array_board) {
+        GameOutcome::Checkmate { winner } => format!("Checkmate! {:?} wins", winner),
+        GameOutcome::Stalemate => "Stalemate! The game is a draw".to_string(),
+        GameOutcome::Draw(reason) => {
+            let reason = match reason {
+                DrawReason::FiftyMoveRule => "the fifty-move rule",
+                DrawReason::ThreefoldRepetition => "threefold repetition",
+                DrawReason::FivefoldRepetition => "fivefold repetition",
+                DrawReason::InsufficientMaterial => "insufficient material",
+            };
+            format!("Draw by {reason}")
+        }
+        GameOutcome::InProgress => return,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            ResultBanner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(20.)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.8).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        message,
+                        TextStyle {
+                            font: asset_server.load("fonts/DejaVuSans-Bold.ttf"),
+                            font_size: 40.,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn mouse_click_system(
     keyboard_input: Res<Input<KeyCode>>,
     mouse_button_input: Res<Input<MouseButton>>,
-    mut array_board: ResMut<ArrayBoard>,
+    mut array_board: ResMut<GameBoard>,
+    mut game_record: ResMut<GameRecord>,
+    mut move_history: ResMut<MoveHistory>,
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
-    piece_query: Query<(Entity, &mut Position, &Piece), Without<CurrentSelectedPiece>>,
+    mut piece_query: Query<(Entity, &mut Transform, &mut Position, &GamePiece), Without<CurrentSelectedPiece>>,
     mut curr_piece_query: Query<(
         Entity,
         &mut Transform,
         &mut Position,
-        &Piece,
+        &GamePiece,
         &CurrentSelectedPiece,
     )>,
     red_tiles: Query<Entity, With<Redtile>>,
+    promotion_choices: Query<(Entity, &PromotionChoice)>,
+    asset_server: Res<AssetServer>,
+    computer: Option<Res<ComputerPlayer>>,
 ) {
     // Check for 'Escape' key to unselect the current piece
     if keyboard_input.just_pressed(KeyCode::Escape) {
         //Escape
+        despawn_promotion_choices(&mut commands, &promotion_choices);
         deselect_current_piece(curr_piece_query, commands, red_tiles);
         return;
     }
@@ -326,59 +206,104 @@ pub fn mouse_click_system(
     if !mouse_button_input.just_pressed(MouseButton::Left) {
         return;
     }
+    // the computer's pieces are moved by the computer
+    if computer.is_some_and(|computer| computer.colour == array_board.turn) {
+        return;
+    }
 
     let window = window_query.get_single().unwrap();
     let horiz_displacement = (window.width() / 2.) - (TILE_SIZE * 3.5);
     let vert_displacement = (window.height() / 2.) - (TILE_SIZE * 3.5);
 
-    let mouse_tile = find_mouse_tile(window.cursor_position().unwrap(), window);
-
     // Deal with input not on the board by doing nothing
-    if mouse_tile[0] >= BOARD_SIZE as f32
-        || mouse_tile[0] < 0.
-        || mouse_tile[1] >= BOARD_SIZE as f32 && mouse_tile[1] < 0.
-    {
+    let Some(mut mouse_tile) = find_mouse_tile(window.cursor_position().unwrap(), window) else {
         return;
-    }
+    };
     // if selected piece is picked up, set it down at the tile
     // if piece is already picked up/invalid move square, do nothing (todo! or error noise? blinking red or something)
     match curr_piece_query.get_single_mut() {
         // has piece picked up
         Ok((curr_entity, mut curr_trans, mut curr_pos, piece_qual, _curr_sel_piece)) => {
-            let curr_valid_tiles = if array_board.in_check.is_some() { in_check_valid_tiles(curr_pos.x, curr_pos.y, piece_qual, &array_board) } 
-                else { valid_tiles(curr_pos.x, curr_pos.y, piece_qual, &array_board, true) };
-            if mouse_tile[0] == curr_pos.x && mouse_tile[1] == curr_pos.y || !curr_valid_tiles.contains(&mouse_tile) {
-                deselect_current_piece(curr_piece_query, commands, red_tiles);
-                return;
+            // While the promotion chooser is open a click either picks a piece or cancels the move
+            let mut promotion = None;
+            if !promotion_choices.is_empty() {
+                let chosen = promotion_choices
+                    .iter()
+                    .find(|(_entity, choice)| choice.tile == mouse_tile)
+                    .map(|(_entity, choice)| (choice.piece_type, choice.target));
+                despawn_promotion_choices(&mut commands, &promotion_choices);
+                match chosen {
+                    Some((piece_type, target)) => {
+                        promotion = Some(piece_type);
+                        mouse_tile = target;
+                    }
+                    None => {
+                        deselect_current_piece(curr_piece_query, commands, red_tiles);
+                        return;
+                    }
+                }
             }
 
-            if (array_board.turn.is_white() && !piece_qual.colour.is_white())
-                || (!array_board.turn.is_white() && piece_qual.colour.is_white())
-            {
+            // Only moves of the side to move show up here, so this also enforces turns
+            let candidate_moves: Vec<Move> = legal_moves(&array_board)
+                .into_iter()
+                .filter(|mv| mv.from == curr_pos.0 && mv.to == mouse_tile)
+                .collect();
+            if candidate_moves.is_empty() {
+                deselect_current_piece(curr_piece_query, commands, red_tiles);
                 return;
             }
 
-            if !(curr_valid_tiles.contains(&mouse_tile)) {
-                // insert error noise or blinking? to signal wrong move
+            // A pawn reaching the last rank waits for the player to pick its new piece
+            if promotion.is_none() && candidate_moves.iter().any(|mv| mv.promotion.is_some()) {
+                spawn_promotion_choices(
+                    &mut commands,
+                    &asset_server,
+                    &piece_qual.colour,
+                    mouse_tile,
+                    horiz_displacement,
+                    vert_displacement,
+                );
                 return;
             }
+            let Some(mv) = candidate_moves.into_iter().find(|mv| mv.promotion == promotion) else {
+                return;
+            };
             // Then if it is a valid tile, move the piece there
-            for (entity, position, _piece_qual) in piece_query.into_iter() {
-                if position.x == mouse_tile[0] && position.y == mouse_tile[1] {
-                    commands.entity(entity).despawn();
+            // (an en passant capture takes the pawn beside the moving pawn instead)
+            if mv.captured.is_some() {
+                let captured_tile = if mv.is_en_passant { Square::new(mv.to.file(), mv.from.rank()) } else { mv.to };
+                for (entity, _transform, position, _piece_qual) in piece_query.iter() {
+                    if position.0 == captured_tile {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+            // Castling moves the rook along with the king
+            if mv.is_castling {
+                if let Some((rook_curr, rook_new)) = castling_rook_tiles(mv.from, mv.to) {
+                    for (_entity, mut rook_trans, mut rook_pos, _piece_qual) in piece_query.iter_mut() {
+                        if rook_pos.0 == rook_curr {
+                            rook_trans.translation += tile_offset(rook_curr, rook_new);
+                            rook_pos.0 = rook_new;
+                        }
+                    }
                 }
             }
 
-            array_board.move_piece((curr_pos.x, curr_pos.y), (mouse_tile[0], mouse_tile[1]));
+            game_record.game.moves.push(mv);
+            // a new move replaces whatever was undone before it
+            move_history.undo.push(array_board.make_move(mv));
+            move_history.redo.clear();
+            if let Some(piece_type) = mv.promotion {
+                let promoted = Piece::new(piece_qual.colour, piece_type);
+                commands
+                    .entity(curr_entity)
+                    .insert((GamePiece(promoted), asset_server.load::<Image, _>(sprite_path(&promoted))));
+            }
 
-            let direction = Vec3::new(
-                (mouse_tile[0] - curr_pos.x) * TILE_SIZE,
-                (mouse_tile[1] - curr_pos.y) * TILE_SIZE,
-                0.,
-            );
-            curr_pos.x = mouse_tile[0];
-            curr_pos.y = mouse_tile[1];
-            curr_trans.translation += direction;
+            curr_trans.translation += tile_offset(curr_pos.0, mv.to);
+            curr_pos.0 = mv.to;
             commands
                 .entity(curr_entity)
                 .remove::<CurrentSelectedPiece>();
@@ -387,18 +312,12 @@ pub fn mouse_click_system(
                     commands.entity(tile).despawn();
                 }
             }
-            array_board.swap_turn(); 
- 
-            array_board.in_check = None;
-            if can_take_king((curr_pos.x, curr_pos.y), (curr_pos.x, curr_pos.y), &piece_qual.colour.opposite(), &array_board) {
-                array_board.in_check = Some(piece_qual.colour.opposite());
-            }
         }
         // no piece picked up
         Err(_) => {
             // if piece occupies the square, pick piece "up"
-            for (entity, position, piece_qual) in piece_query.into_iter() {
-                if position.x != mouse_tile[0] || position.y != mouse_tile[1] {
+            for (entity, _transform, position, _piece_qual) in piece_query.iter() {
+                if position.0 != mouse_tile {
                     continue;
                 }
                 commands.entity(entity).insert(CurrentSelectedPiece);
@@ -406,22 +325,21 @@ pub fn mouse_click_system(
                 // Show tiles able to move onto 
                 spawn_red_tile(
                     &mut commands,
-                    mouse_tile[0],
-                    mouse_tile[1],
+                    mouse_tile,
                     horiz_displacement,
                     vert_displacement,
                 );
-                let curr_valid_tiles = {
-                    if array_board.in_check.is_some() && !array_board.in_check.unwrap().is_different(&piece_qual.colour) { 
-                        in_check_valid_tiles(position.x, position.y, piece_qual, &array_board) 
-                    } else {
-                        valid_tiles(position.x, position.y, piece_qual, &array_board, true) 
-                }};
+                let mut curr_valid_tiles: Vec<Square> = legal_moves(&array_board)
+                    .into_iter()
+                    .filter(|mv| mv.from == position.0)
+                    .map(|mv| mv.to)
+                    .collect();
+                // promotions give one move per piece but they all share a tile
+                curr_valid_tiles.dedup();
                 for valid_pos in curr_valid_tiles {
                     spawn_red_tile(
                         &mut commands,
-                        valid_pos.x,
-                        valid_pos.y,
+                        valid_pos,
                         horiz_displacement,
                         vert_displacement,
                     );
@@ -437,41 +355,123 @@ fn deselect_current_piece(
         Entity,
         &mut Transform,
         &mut Position,
-        &Piece,
+        &GamePiece,
         &CurrentSelectedPiece,
     )>,
     mut commands: Commands,
     red_tiles: Query<Entity, With<Redtile>>,
 ) {
-    match curr_piece_query.get_single_mut() {
-        Ok((curr_entity, _curr_trans, _curr_pos, _piece_qual, _curr_sel_piece)) => {
-            commands
-                .entity(curr_entity)
-                .remove::<CurrentSelectedPiece>();
-            if !red_tiles.is_empty() {
-                for tile in red_tiles.into_iter() {
-                    commands.entity(tile).despawn();
-                }
+    if let Ok((curr_entity, _curr_trans, _curr_pos, _piece_qual, _curr_sel_piece)) = curr_piece_query.get_single_mut() {
+        commands
+            .entity(curr_entity)
+            .remove::<CurrentSelectedPiece>();
+        if !red_tiles.is_empty() {
+            for tile in red_tiles.into_iter() {
+                commands.entity(tile).despawn();
             }
         }
-        Err(_) => (),
     }
 } 
 
-pub fn find_mouse_tile(input: Vec2, window: &Window) -> Vec2 {
+fn despawn_promotion_choices(
+    commands: &mut Commands,
+    promotion_choices: &Query<(Entity, &PromotionChoice)>,
+) {
+    for (entity, _choice) in promotion_choices.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Lays the four promotion pieces out from the promotion square towards the middle of the board
+fn spawn_promotion_choices(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    colour: &PieceColour,
+    target: Square,
+    horiz_displacement: f32,
+    vert_displacement: f32,
+) {
+    let direction = if colour.is_white() { -1 } else { 1 };
+    let choices = [PieceType::Queen, PieceType::Knight, PieceType::Rook, PieceType::Bishop];
+    for (i, piece_type) in choices.into_iter().enumerate() {
+        let Some(tile) = target.offset(0, direction * i as i8) else { continue; };
+        commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb_u8(240, 240, 240),
+                        rect: Some(Rect::new(0., 0., TILE_SIZE, TILE_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(tile_translation(
+                        tile,
+                        horiz_displacement,
+                        vert_displacement,
+                        1.,
+                    )),
+                    ..default()
+                },
+                PromotionChoice {
+                    piece_type,
+                    tile,
+                    target,
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(SpriteBundle {
+                    transform: Transform::from_xyz(0., 0., 1.).with_scale(Vec3::new(0.25, 0.25, 1.)),
+                    texture: asset_server.load(sprite_path(&Piece::new(*colour, piece_type))),
+                    ..default()
+                });
+            });
+    }
+}
+
+pub fn sprite_path(piece: &Piece) -> String {
+    let colour = if piece.colour.is_white() { "W" } else { "B" };
+    let name = match piece.piece_type {
+        PieceType::Pawn => "pawn",
+        PieceType::King => "king",
+        PieceType::Queen => "queen",
+        PieceType::Rook => "rook",
+        PieceType::Bishop => "bishop",
+        PieceType::Knight => "horse",
+    };
+    format!("sprites/{colour}{name}.png")
+}
+
+// Board squares only exist as world-space coordinates from here down
+pub fn find_mouse_tile(input: Vec2, window: &Window) -> Option<Square> {
     let horiz_displacement = window.width() / 2. - TILE_SIZE * 4.;
     let vert_displacement = window.height() / 2. - TILE_SIZE * 4.;
-    Vec2::new(
-        f32::floor((input[0] - horiz_displacement) / TILE_SIZE),
-        f32::floor((input[1] - vert_displacement) / TILE_SIZE),
+    let file = f32::floor((input[0] - horiz_displacement) / TILE_SIZE);
+    let rank = f32::floor((input[1] - vert_displacement) / TILE_SIZE);
+    if !(0. ..BOARD_SIZE as f32).contains(&file) || !(0. ..BOARD_SIZE as f32).contains(&rank) {
+        return None;
+    }
+    Some(Square::new(file as u8, rank as u8))
+}
+
+pub fn tile_translation(square: Square, horiz_displacement: f32, vert_displacement: f32, z: f32) -> Vec3 {
+    Vec3::new(
+        (square.file() as f32 * TILE_SIZE) + horiz_displacement,
+        (square.rank() as f32 * TILE_SIZE) + vert_displacement,
+        z,
+    )
+}
+
+// How far a sprite travels moving between two squares
+fn tile_offset(from: Square, to: Square) -> Vec3 {
+    Vec3::new(
+        (to.file() as f32 - from.file() as f32) * TILE_SIZE,
+        (to.rank() as f32 - from.rank() as f32) * TILE_SIZE,
+        0.,
     )
-    // return is 0 indexed!
 }
 
 pub fn spawn_red_tile(
     commands: &mut Commands,
-    pos_x: f32,
-    pos_y: f32,
+    square: Square,
     horiz_displacement: f32,
     vert_displacement: f32, 
 ) {
@@ -482,11 +482,12 @@ pub fn spawn_red_tile(
                 rect: Some(Rect::new(0., 0., TILE_SIZE, TILE_SIZE)),
                 ..default()
             },
-            transform: Transform::from_xyz(
-                (pos_x * TILE_SIZE) + horiz_displacement,
-                (pos_y * TILE_SIZE) + vert_displacement,
+            transform: Transform::from_translation(tile_translation(
+                square,
+                horiz_displacement,
+                vert_displacement,
                 0.,
-            ),
+            )),
             ..default()
         },
         Redtile,
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::tasks::Task;
+use chess_core::{ArrayBoard, Move, PgnGame, Piece, PieceColour, PieceType, Square, UndoInfo};
+use chess_engine::{EndgameTables, SearchLimits, SearchOptions, SearchResult, TranspositionTable};
+
+// The rules engine's board as the game's shared state
+#[derive(Resource, Clone, Default, Deref, DerefMut)]
+pub struct GameBoard(pub ArrayBoard);
+
+// Every move played in the window, kept so the game can be saved as PGN
+#[derive(Resource, Clone)]
+pub struct GameRecord {
+    pub game: PgnGame,
+    // where Ctrl+S and the end of the game write the PGN
+    pub path: PathBuf,
+}
+
+// Moves that can be taken back (latest last) and moves taken back that can be played again
+#[derive(Resource, Default)]
+pub struct MoveHistory {
+    pub undo: Vec<UndoInfo>,
+    pub redo: Vec<Move>,
+}
+
+// The side the engine plays in a game against the computer
+#[derive(Resource)]
+pub struct ComputerPlayer {
+    pub colour: PieceColour,
+    pub limits: SearchLimits,
+    pub options: SearchOptions,
+    // kept from move to move and shared with the search task
+    pub tt: Arc<TranspositionTable>,
+    // the search running in the background while it is the computer's turn
+    pub thinking: Option<ComputerSearch>,
+}
+
+pub struct ComputerSearch {
+    pub task: Task<SearchResult>,
+    pub stop: Arc<AtomicBool>,
+    // zobrist key of the position searched, a result for any other position is thrown away
+    pub position: u64,
+}
+
+// Endgame tables loaded with --endgames, for the H key's best move hint
+#[derive(Resource, Clone, Deref)]
+pub struct EndgameHint(pub Arc<EndgameTables>);
+
+// Games loaded from a PGN file and how far into which one the board is showing
+#[derive(Resource, Clone)]
+pub struct Replay {
+    pub games: Vec<PgnGame>,
+    pub game_index: usize,
+    // moves of the chosen game played so far on the board
+    pub ply: usize,
+}
+
+// The piece a sprite stands for
+#[derive(Component, Clone, Copy, Deref, DerefMut)]
+pub struct GamePiece(pub Piece);
+
+#[derive(Component)]
+pub struct CurrentSelectedPiece;
+
+#[derive(PartialEq, Component)]
+pub struct Position(pub Square);
+#[derive(Component)]
+pub struct Redtile;
+
+// One of the pieces offered when a pawn reaches the last rank
+#[derive(Component)]
+pub struct PromotionChoice {
+    pub piece_type: PieceType,
+    pub tile: Square,
+    pub target: Square,
+}
+
+// The replay viewer's side panel, rebuilt whenever the replay moves on
+#[derive(Component)]
+pub struct ReplayPanel;
+
+// A button in the replay panel that switches to the game at this index
+#[derive(Component)]
+pub struct GameListEntry(pub usize);
+
+#[derive(Component)]
+pub struct ResultBanner;
+
+// The on-screen undo and redo buttons
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryButton {
+    Undo,
+    Redo,
+}
+
+// The squares of the hinted move and the line saying how the ending goes, until the position changes
+#[derive(Component)]
+pub struct HintTile;
+
+#[derive(Component)]
+pub struct HintText;
@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::tasks::AsyncComputeTaskPool;
+use bevy::{app::AppExit, prelude::*, window::PrimaryWindow};
+use chess_core::PieceColour;
+use chess_engine::tt::DEFAULT_HASH_MB;
+use chess_engine::{search, SearchLimits, SearchOptions, TranspositionTable};
+use futures_lite::future;
+
+use super::components::{ComputerPlayer, ComputerSearch, GameBoard, GamePiece, GameRecord, MoveHistory};
+use super::history::PositionOverlays;
+use super::redraw_pieces;
+
+// How long the computer thinks about each move
+const THINKING_TIME: Duration = Duration::from_secs(1);
+
+impl ComputerPlayer {
+    pub fn new(colour: PieceColour, threads: usize) -> Self {
+        ComputerPlayer {
+            colour,
+            limits: SearchLimits::movetime(THINKING_TIME),
+            options: SearchOptions {
+                threads,
+                ..Default::default()
+            },
+            tt: Arc::new(TranspositionTable::new(DEFAULT_HASH_MB)),
+            thinking: None,
+        }
+    }
+    // Stops the search if one is running and forgets about its move
+    pub fn cancel(&mut self) {
+        if let Some(thinking) = self.thinking.take() {
+            thinking.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+// Starts the engine on its own thread once it is the computer's turn, so the window keeps drawing while it thinks
+pub fn start_computer_search(mut computer: ResMut<ComputerPlayer>, array_board: Res<GameBoard>) {
+    if computer.thinking.is_some() || array_board.turn != computer.colour {
+        return;
+    }
+    let board = array_board.0.clone();
+    let limits = computer.limits.clone();
+    let stop = Arc::new(AtomicBool::new(false));
+    let task_stop = Arc::clone(&stop);
+    let options = computer.options.clone();
+    let tt = Arc::clone(&computer.tt);
+    let task = AsyncComputeTaskPool::get().spawn(async move { search(&board, &limits, &options, &tt, &task_stop, |_info| ()) });
+    computer.thinking = Some(ComputerSearch {
+        task,
+        stop,
+        position: array_board.zobrist_key(),
+    });
+}
+
+// Space cuts the computer's thinking short, it then plays the best move it has found so far
+pub fn computer_move_now(keyboard_input: Res<Input<KeyCode>>, computer: Res<ComputerPlayer>) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        if let Some(thinking) = &computer.thinking {
+            thinking.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+// Closing the window stops the search threads rather than leaving them to finish their move
+pub fn stop_computer_on_exit(mut exits: EventReader<AppExit>, mut computer: ResMut<ComputerPlayer>) {
+    if exits.iter().next().is_some() {
+        computer.cancel();
+    }
+}
+
+// Plays the computer's move once its search has finished
+#[allow(clippy::too_many_arguments)]
+pub fn play_computer_move(
+    mut commands: Commands,
+    mut computer: ResMut<ComputerPlayer>,
+    mut array_board: ResMut<GameBoard>,
+    mut game_record: ResMut<GameRecord>,
+    mut move_history: ResMut<MoveHistory>,
+    pieces: Query<Entity, With<GamePiece>>,
+    overlays: Query<Entity, PositionOverlays>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(thinking) = computer.thinking.as_mut() else { return; };
+    let Some(result) = future::block_on(future::poll_once(&mut thinking.task)) else { return; };
+    let position = thinking.position;
+    computer.thinking = None;
+    let Some(mv) = result.best_move else { return; };
+    // the position may have been taken back while the engine was thinking
+    if array_board.zobrist_key() != position {
+        return;
+    }
+
+    game_record.game.moves.push(mv);
+    move_history.undo.push(array_board.make_move(mv));
+    move_history.redo.clear();
+    for entity in overlays.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    redraw_pieces(&mut commands, &asset_server, &array_board, &pieces, window_query.single());
+}
@@ -0,0 +1,87 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use chess_core::Square;
+use chess_engine::endgame::EndgameValue;
+
+use super::components::{EndgameHint, GameBoard, HintText, HintTile};
+use super::{tile_translation, TILE_SIZE};
+
+// The tiles and text of the hint on screen
+pub type HintOverlays = Or<(With<HintTile>, With<HintText>)>;
+
+// H shows the endgame tables' best move on the board, with who mates in how many moves or that it is a draw
+pub fn show_endgame_hint(
+    keyboard_input: Res<Input<KeyCode>>,
+    endgame_hint: Res<EndgameHint>,
+    array_board: Res<GameBoard>,
+    mut commands: Commands,
+    hints: Query<Entity, HintOverlays>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::H) {
+        return;
+    }
+    for entity in hints.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let message = match endgame_hint.best_move(&array_board) {
+        Some((mv, value)) => {
+            let window = window_query.single();
+            let horiz_displacement = (window.width() / 2.) - (TILE_SIZE * 3.5);
+            let vert_displacement = (window.height() / 2.) - (TILE_SIZE * 3.5);
+            for square in [mv.from, mv.to] {
+                spawn_hint_tile(&mut commands, square, horiz_displacement, vert_displacement);
+            }
+            let outcome = match value {
+                EndgameValue::Win(plies) => format!("{:?} mates in {}", array_board.turn, plies.div_ceil(2)),
+                EndgameValue::Loss(plies) => format!("{:?} mates in {}", array_board.turn.opposite(), plies.div_ceil(2)),
+                EndgameValue::Draw => "Draw with best play".to_string(),
+            };
+            format!("{}: {outcome}", mv.to_uci())
+        }
+        None => "No endgame table for this position".to_string(),
+    };
+    commands.spawn((
+        TextBundle::from_section(
+            message,
+            TextStyle {
+                font: asset_server.load("fonts/DejaVuSans-Bold.ttf"),
+                font_size: 22.,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(10.),
+                top: Val::Px(10.),
+                ..default()
+            },
+            ..default()
+        }),
+        HintText,
+    ));
+}
+
+// A hint is only good for the position it was asked for
+pub fn clear_endgame_hint(mut commands: Commands, hints: Query<Entity, HintOverlays>) {
+    for entity in hints.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn spawn_hint_tile(commands: &mut Commands, square: Square, horiz_displacement: f32, vert_displacement: f32) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb_u8(120, 190, 110),
+                rect: Some(Rect::new(0., 0., TILE_SIZE, TILE_SIZE)),
+                ..default()
+            },
+            // above the board tiles (-1.) but under the pieces (0.) so the piece to move stays visible
+            transform: Transform::from_translation(tile_translation(square, horiz_displacement, vert_displacement, -0.5)),
+            ..default()
+        },
+        HintTile,
+    ));
+}
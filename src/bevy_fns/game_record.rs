@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use chess_core::pgn::result_tag;
+use chess_core::{game_outcome, ArrayBoard, PgnGame};
+
+use super::components::{GameBoard, GameRecord};
+
+// Saved games go here, one file per game named after the time it started
+const SAVE_DIR: &str = "games";
+
+impl GameRecord {
+    pub fn new(start: &ArrayBoard) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let mut game = PgnGame::new(start.clone());
+        game.set_tag("Event", "Casual game");
+        game.set_tag("Site", "chess-rust-project");
+        game.set_tag("Date", &pgn_date(seconds));
+        game.set_tag("Round", "-");
+        GameRecord {
+            game,
+            path: PathBuf::from(SAVE_DIR).join(format!("game-{seconds}.pgn")),
+        }
+    }
+}
+
+// Ctrl+S writes the game so far
+pub fn save_pgn_shortcut(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_record: Res<GameRecord>,
+    array_board: Res<GameBoard>,
+) {
+    if keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) && keyboard_input.just_pressed(KeyCode::S) {
+        save_pgn(&game_record, &array_board);
+    }
+}
+
+// A finished game is saved without being asked
+pub fn save_pgn_at_game_end(game_record: Res<GameRecord>, array_board: Res<GameBoard>) {
+    save_pgn(&game_record, &array_board);
+}
+
+fn save_pgn(game_record: &GameRecord, array_board: &ArrayBoard) {
+    let mut game = game_record.game.clone();
+    game.set_tag("Result", result_tag(&game_outcome(array_board)));
+    let path = &game_record.path;
+    match fs::create_dir_all(SAVE_DIR).and_then(|_| fs::write(path, game.to_pgn())) {
+        Ok(()) => info!("saved the game to {}", path.display()),
+        Err(error) => error!("could not save the game to {}: {error}", path.display()),
+    }
+}
+
+// "YYYY.MM.DD" for a time given in seconds since 1970 (UTC), using the civil-from-days algorithm
+fn pgn_date(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}.{month:02}.{day:02}")
+}
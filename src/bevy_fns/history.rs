@@ -0,0 +1,233 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use chess_core::{ArrayBoard, PieceColour};
+
+use super::components::{ComputerPlayer, GameBoard, GamePiece, GameRecord, HistoryButton, MoveHistory, PromotionChoice, Redtile, ResultBanner};
+use super::{redraw_pieces, GameState};
+
+// Anything on screen that belongs to one position only and has to go when the position jumps
+pub type PositionOverlays = Or<(With<Redtile>, With<PromotionChoice>, With<ResultBanner>)>;
+
+pub fn spawn_history_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/DejaVuSans-Bold.ttf");
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(10.),
+                    top: Val::Px(10.),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                gap: Size::all(Val::Px(6.)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (button, label) in [(HistoryButton::Undo, "Undo (Ctrl+Z)"), (HistoryButton::Redo, "Redo (Ctrl+Y)")] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(8.)),
+                                ..default()
+                            },
+                            background_color: Color::rgb_u8(150, 77, 34).into(),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 18.,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+// Takes back the last move, and against the computer its reply as well, so it is the player's turn again.
+// The computer's opening move stays, taken back it would only be played again straight away.
+// False if there is nothing to take back.
+pub fn undo_move(
+    array_board: &mut ArrayBoard,
+    move_history: &mut MoveHistory,
+    game_record: &mut GameRecord,
+    computer_colour: Option<PieceColour>,
+) -> bool {
+    let Some(players_move) = move_history.undo.iter().rposition(|undo| Some(undo.moved.colour) != computer_colour) else {
+        return false;
+    };
+    for undo in move_history.undo.drain(players_move..).rev() {
+        array_board.unmake_move(undo);
+        move_history.redo.push(undo.mv);
+        game_record.game.moves.pop();
+    }
+    true
+}
+
+// Plays the last move taken back again, and against the computer its reply as well. False if there is none.
+pub fn redo_move(
+    array_board: &mut ArrayBoard,
+    move_history: &mut MoveHistory,
+    game_record: &mut GameRecord,
+    computer_colour: Option<PieceColour>,
+) -> bool {
+    if move_history.redo.is_empty() {
+        return false;
+    }
+    while let Some(mv) = move_history.redo.pop() {
+        move_history.undo.push(array_board.make_move(mv));
+        game_record.game.moves.push(mv);
+        if computer_colour != Some(array_board.turn) {
+            break;
+        }
+    }
+    true
+}
+
+// Ctrl+Z or the Undo button takes back the last move, Ctrl+Y or the Redo button plays it again.
+// The pieces are redrawn from the board afterwards, which brings captured pieces back as well.
+#[allow(clippy::too_many_arguments)]
+pub fn undo_redo_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    buttons: Query<(&Interaction, &HistoryButton), Changed<Interaction>>,
+    mut array_board: ResMut<GameBoard>,
+    mut move_history: ResMut<MoveHistory>,
+    mut game_record: ResMut<GameRecord>,
+    mut commands: Commands,
+    pieces: Query<Entity, With<GamePiece>>,
+    overlays: Query<Entity, PositionOverlays>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut computer: Option<ResMut<ComputerPlayer>>,
+) {
+    let ctrl = keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let clicked = |wanted: HistoryButton| {
+        buttons
+            .iter()
+            .any(|(interaction, button)| *interaction == Interaction::Clicked && *button == wanted)
+    };
+
+    let computer_colour = computer.as_ref().map(|computer| computer.colour);
+    let stepped = if (ctrl && keyboard_input.just_pressed(KeyCode::Z)) || clicked(HistoryButton::Undo) {
+        undo_move(&mut array_board, &mut move_history, &mut game_record, computer_colour)
+    } else if (ctrl && keyboard_input.just_pressed(KeyCode::Y)) || clicked(HistoryButton::Redo) {
+        redo_move(&mut array_board, &mut move_history, &mut game_record, computer_colour)
+    } else {
+        false
+    };
+    if !stepped {
+        return;
+    }
+    // whatever the computer was thinking about is no longer on the board
+    if let Some(computer) = computer.as_mut() {
+        computer.cancel();
+    }
+
+    for entity in overlays.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    redraw_pieces(&mut commands, &asset_server, &array_board, &pieces, window_query.single());
+    // taking back the last move of a finished game carries on playing, check_game_over ends it again if need be
+    if state.0 == GameState::GameOver {
+        next_state.set(GameState::Playing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chess_core::{Move, UndoInfo};
+
+    use super::*;
+
+    // Everything a move taken back and played again has to leave as it was, apart from the redo stack
+    type Snapshot = (String, Vec<u64>, Option<PieceColour>, Vec<UndoInfo>, String);
+
+    fn snapshot(array_board: &ArrayBoard, move_history: &MoveHistory, game_record: &GameRecord) -> Snapshot {
+        (
+            array_board.to_fen(),
+            array_board.history.clone(),
+            array_board.in_check,
+            move_history.undo.clone(),
+            game_record.game.to_pgn(),
+        )
+    }
+
+    // Plays a move the way mouse_click_system and the computer do
+    fn play(array_board: &mut ArrayBoard, move_history: &mut MoveHistory, game_record: &mut GameRecord, uci: &str) -> Move {
+        let mv = array_board.parse_uci(uci).unwrap();
+        game_record.game.moves.push(mv);
+        move_history.undo.push(array_board.make_move(mv));
+        move_history.redo.clear();
+        mv
+    }
+
+    // An en passant capture, castling, a promotion that captures, a plain capture and castling for the other side
+    const START: &str = "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1";
+    const MOVES: [&str; 5] = ["e5d6", "e8g8", "b7a8q", "f8a8", "e1g1"];
+
+    #[test]
+    fn undo_and_redo_restore_every_position() {
+        let mut array_board = ArrayBoard::from_fen(START).unwrap();
+        let mut move_history = MoveHistory::default();
+        let mut game_record = GameRecord::new(&array_board);
+        let mut snapshots = vec![snapshot(&array_board, &move_history, &game_record)];
+        let mut moves: Vec<Move> = vec![];
+        for uci in MOVES {
+            moves.push(play(&mut array_board, &mut move_history, &mut game_record, uci));
+            snapshots.push(snapshot(&array_board, &move_history, &game_record));
+        }
+
+        for ply in (0..MOVES.len()).rev() {
+            assert!(undo_move(&mut array_board, &mut move_history, &mut game_record, None));
+            assert_eq!(snapshot(&array_board, &move_history, &game_record), snapshots[ply], "undoing {}", MOVES[ply]);
+        }
+        assert!(!undo_move(&mut array_board, &mut move_history, &mut game_record, None));
+        assert_eq!(move_history.redo, moves.iter().rev().copied().collect::<Vec<Move>>());
+
+        for ply in 1..=MOVES.len() {
+            assert!(redo_move(&mut array_board, &mut move_history, &mut game_record, None));
+            assert_eq!(snapshot(&array_board, &move_history, &game_record), snapshots[ply], "redoing {}", MOVES[ply - 1]);
+        }
+        assert!(!redo_move(&mut array_board, &mut move_history, &mut game_record, None));
+        assert!(move_history.redo.is_empty());
+    }
+
+    #[test]
+    fn undo_against_the_computer_keeps_its_opening_move() {
+        let mut array_board = ArrayBoard::from_fen(chess_core::START_FEN).unwrap();
+        let mut move_history = MoveHistory::default();
+        let mut game_record = GameRecord::new(&array_board);
+        let computer = Some(PieceColour::White);
+        for uci in ["e2e4", "e7e5", "g1f3", "b8c6"] {
+            play(&mut array_board, &mut move_history, &mut game_record, uci);
+        }
+        let ucis = |moves: &[Move]| moves.iter().map(|mv| mv.to_uci()).collect::<Vec<String>>();
+
+        // the player's move goes, leaving the computer's reply to it
+        assert!(undo_move(&mut array_board, &mut move_history, &mut game_record, computer));
+        assert_eq!(ucis(&game_record.game.moves), ["e2e4", "e7e5", "g1f3"]);
+        // the computer's reply goes with the player's move before it
+        assert!(undo_move(&mut array_board, &mut move_history, &mut game_record, computer));
+        assert_eq!(ucis(&game_record.game.moves), ["e2e4"]);
+        assert_eq!(array_board.turn, PieceColour::Black);
+        // only the computer's opening move is left, and it stays
+        assert!(!undo_move(&mut array_board, &mut move_history, &mut game_record, computer));
+        assert_eq!(move_history.undo.len(), 1);
+        assert_eq!(array_board.turn, PieceColour::Black);
+
+        // redo plays the player's move and the computer's reply together
+        assert!(redo_move(&mut array_board, &mut move_history, &mut game_record, computer));
+        assert_eq!(ucis(&game_record.game.moves), ["e2e4", "e7e5", "g1f3"]);
+        assert_eq!(array_board.turn, PieceColour::Black);
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use chess_core::{ArrayBoard, PgnGame};
+
+use super::components::{GameBoard, GameListEntry, GamePiece, Replay, ReplayPanel};
+use super::redraw_pieces;
+
+// How many games the panel lists at once, centred on the chosen one
+const LISTED_GAMES: usize = 15;
+
+impl Replay {
+    pub fn new(games: Vec<PgnGame>) -> Self {
+        Replay {
+            games,
+            game_index: 0,
+            ply: 0,
+        }
+    }
+    pub fn game(&self) -> &PgnGame {
+        &self.games[self.game_index]
+    }
+    // The chosen game's position after `ply` moves
+    pub fn position(&self) -> ArrayBoard {
+        let mut array_board = self.game().start.clone();
+        for mv in &self.game().moves[..self.ply] {
+            array_board.make_move(*mv);
+        }
+        array_board
+    }
+}
+
+// Left/Right step through the moves, Home/End jump to either end, PageUp/PageDown change game
+pub fn replay_keys(keyboard_input: Res<Input<KeyCode>>, mut replay: ResMut<Replay>) {
+    let last_ply = replay.game().moves.len();
+    let last_game = replay.games.len() - 1;
+    let (mut game_index, mut ply) = (replay.game_index, replay.ply);
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        ply = (ply + 1).min(last_ply);
+    }
+    if keyboard_input.just_pressed(KeyCode::Left) {
+        ply = ply.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::Home) {
+        ply = 0;
+    }
+    if keyboard_input.just_pressed(KeyCode::End) {
+        ply = last_ply;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        game_index = (game_index + 1).min(last_game);
+    }
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        game_index = game_index.saturating_sub(1);
+    }
+    if game_index != replay.game_index {
+        // a new game starts from its first position
+        replay.game_index = game_index;
+        replay.ply = 0;
+    } else if ply != replay.ply {
+        replay.ply = ply;
+    }
+}
+
+pub fn replay_game_list_clicks(
+    interactions: Query<(&Interaction, &GameListEntry), Changed<Interaction>>,
+    mut replay: ResMut<Replay>,
+) {
+    for (interaction, entry) in interactions.iter() {
+        if *interaction == Interaction::Clicked && entry.0 != replay.game_index {
+            replay.game_index = entry.0;
+            replay.ply = 0;
+        }
+    }
+}
+
+// Puts the pieces where the replay has got to
+pub fn show_replay_position(
+    mut commands: Commands,
+    replay: Res<Replay>,
+    mut array_board: ResMut<GameBoard>,
+    pieces: Query<Entity, With<GamePiece>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+) {
+    array_board.0 = replay.position();
+    redraw_pieces(&mut commands, &asset_server, &array_board, &pieces, window_query.single());
+}
+
+pub fn show_replay_panel(
+    mut commands: Commands,
+    replay: Res<Replay>,
+    panels: Query<Entity, With<ReplayPanel>>,
+    asset_server: Res<AssetServer>,
+) {
+    for entity in panels.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let font = asset_server.load("fonts/DejaVuSans-Bold.ttf");
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font.clone(),
+        font_size,
+        color,
+    };
+
+    let game = replay.game();
+    let tag = |name: &str| game.tag(name).unwrap_or("?").to_string();
+    let move_line = if replay.ply == 0 {
+        format!("Start of game, {} moves", game.moves.len())
+    } else {
+        let mut before = replay.game().start.clone();
+        for mv in &game.moves[..replay.ply - 1] {
+            before.make_move(*mv);
+        }
+        let number = if before.turn.is_white() {
+            format!("{}.", before.fullmove_number)
+        } else {
+            format!("{}...", before.fullmove_number)
+        };
+        let san = game.moves[replay.ply - 1].to_san(&before);
+        format!("Move {} of {}: {number} {san}", replay.ply, game.moves.len())
+    };
+    let lines = [
+        format!("{} - {}", tag("White"), tag("Black")),
+        format!("{}, {} ({})", tag("Event"), tag("Date"), tag("Result")),
+        move_line,
+        "Left/Right step, Home/End jump".to_string(),
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(10.),
+                        top: Val::Px(10.),
+                        ..default()
+                    },
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.8).into(),
+                ..default()
+            },
+            ReplayPanel,
+        ))
+        .with_children(|parent| {
+            for line in lines {
+                parent.spawn(TextBundle::from_section(line, text_style(16., Color::WHITE)));
+            }
+            if replay.games.len() < 2 {
+                return;
+            }
+            parent.spawn(TextBundle::from_section(
+                format!("Game {} of {}, PageUp/PageDown or click to change", replay.game_index + 1, replay.games.len()),
+                text_style(16., Color::WHITE),
+            ));
+            let first = replay
+                .game_index
+                .saturating_sub(LISTED_GAMES / 2)
+                .min(replay.games.len().saturating_sub(LISTED_GAMES));
+            for (index, listed) in replay.games.iter().enumerate().skip(first).take(LISTED_GAMES) {
+                let background = if index == replay.game_index {
+                    Color::rgb_u8(150, 77, 34)
+                } else {
+                    Color::rgba(0., 0., 0., 0.)
+                };
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(2.)),
+                                ..default()
+                            },
+                            background_color: background.into(),
+                            ..default()
+                        },
+                        GameListEntry(index),
+                    ))
+                    .with_children(|button| {
+                        let name = |tag: &str| listed.tag(tag).unwrap_or("?").to_string();
+                        button.spawn(TextBundle::from_section(
+                            format!("{}. {} - {} {}", index + 1, name("White"), name("Black"), name("Result")),
+                            text_style(14., Color::rgb_u8(218, 217, 181)),
+                        ));
+                    });
+            }
+        });
+}
@@ -1,19 +1,194 @@
 #![deny(clippy::all)]
 
 mod bevy_fns;
-use bevy_fns::array::ArrayBoard;
-use bevy_fns::{setup_board, spawn_camera, mouse_click_system};
+use bevy_fns::components::{ComputerPlayer, EndgameHint, GameBoard, GameRecord, MoveHistory, Replay};
+use bevy_fns::computer::{computer_move_now, play_computer_move, start_computer_search, stop_computer_on_exit};
+use bevy_fns::endgame_hint::{clear_endgame_hint, show_endgame_hint};
+use bevy_fns::game_record::{save_pgn_at_game_end, save_pgn_shortcut};
+use bevy_fns::history::{spawn_history_buttons, undo_redo_system};
+use bevy_fns::replay::{replay_game_list_clicks, replay_keys, show_replay_panel, show_replay_position};
+use bevy_fns::{setup_board, spawn_camera, mouse_click_system, check_game_over, spawn_result_banner, GameState};
+
+use std::sync::Arc;
 
 use bevy::prelude::*; 
+use chess_core::{parse_pgn, ArrayBoard, PieceColour};
+use chess_engine::book::load_book;
+use chess_engine::endgame::load_endgames;
+use chess_engine::eval::load_params;
+use chess_engine::search::MAX_THREADS;
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
+    let replay = pgn_replay();
+    let game_board = match &replay {
+        Some(replay) => GameBoard(replay.position()),
+        None => starting_board(),
+    };
+    let game_record = GameRecord::new(&game_board);
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
         .add_startup_system(spawn_camera)
         .add_startup_system(setup_board)
-        .add_system(mouse_click_system)
-        .insert_resource(ArrayBoard { ..Default::default() }) 
-        .run();
+        .add_state::<GameState>()
+        .add_system(mouse_click_system.in_set(OnUpdate(GameState::Playing)))
+        .add_system(
+            check_game_over
+                .run_if(resource_changed::<GameBoard>())
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(spawn_result_banner.in_schedule(OnEnter(GameState::GameOver)))
+        .add_startup_system(spawn_history_buttons.run_if(not(resource_exists::<Replay>())))
+        .add_system(undo_redo_system.run_if(not(in_state(GameState::Replay))))
+        .add_system(save_pgn_shortcut.run_if(not(in_state(GameState::Replay))))
+        .add_system(save_pgn_at_game_end.in_schedule(OnEnter(GameState::GameOver)))
+        .add_systems(
+            (start_computer_search, computer_move_now, play_computer_move)
+                .chain()
+                .distributive_run_if(resource_exists::<ComputerPlayer>())
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_systems(
+            (show_endgame_hint.in_set(OnUpdate(GameState::Playing)), clear_endgame_hint.run_if(resource_changed::<GameBoard>()))
+                .distributive_run_if(resource_exists::<EndgameHint>()),
+        )
+        .add_system(stop_computer_on_exit.run_if(resource_exists::<ComputerPlayer>()))
+        .add_systems((replay_keys, replay_game_list_clicks).in_set(OnUpdate(GameState::Replay)))
+        .add_systems(
+            (show_replay_position, show_replay_panel)
+                .distributive_run_if(resource_exists_and_changed::<Replay>())
+                .in_set(OnUpdate(GameState::Replay)),
+        )
+        .insert_resource(game_board)
+        .insert_resource(game_record)
+        .init_resource::<MoveHistory>();
+    let endgame_hint = endgame_hint();
+    if let Some(endgame_hint) = &endgame_hint {
+        app.insert_resource(endgame_hint.clone());
+    }
+    if let Some(replay) = replay {
+        app.insert_resource(replay)
+            .insert_resource(NextState(Some(GameState::Replay)));
+    } else if let Some(mut computer) = computer_player() {
+        computer.options.endgames = endgame_hint.map(|endgame_hint| endgame_hint.0);
+        app.insert_resource(computer);
+    }
+    app.run();
+}
+
+// `cargo run -- --pgn <file>` opens the games in a PGN file for replaying instead of starting a new game
+fn pgn_replay() -> Option<Replay> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|arg| arg == "--pgn")?;
+    let Some(path) = args.get(i + 1) else {
+        eprintln!("--pgn needs a PGN file after it");
+        std::process::exit(1);
+    };
+    let text = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("could not read {path}: {error}");
+        std::process::exit(1);
+    });
+    match parse_pgn(&text) {
+        Ok(games) if games.is_empty() => {
+            eprintln!("{path} has no games in it");
+            std::process::exit(1);
+        }
+        Ok(games) => Some(Replay::new(games)),
+        Err(error) => {
+            eprintln!("{path}, {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// `cargo run -- --computer white|black` has the computer play that side,
+// searching on every core unless `--threads <n>` says how many threads to use,
+// evaluating with the parameters `--eval <file>` from the tune binary if given,
+// and opening from the Polyglot book `--book <file>` if given
+fn computer_player() -> Option<ComputerPlayer> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|arg| arg == "--computer")?;
+    let colour = match args.get(i + 1).map(String::as_str) {
+        Some("white") => PieceColour::White,
+        Some("black") => PieceColour::Black,
+        _ => {
+            eprintln!("--computer needs white or black after it");
+            std::process::exit(1);
+        }
+    };
+    let threads = match args.iter().position(|arg| arg == "--threads") {
+        Some(i) => match args.get(i + 1).and_then(|threads| threads.parse::<usize>().ok()) {
+            Some(threads) if (1..=MAX_THREADS).contains(&threads) => threads,
+            _ => {
+                eprintln!("--threads needs a number from 1 to {MAX_THREADS} after it");
+                std::process::exit(1);
+            }
+        },
+        None => std::thread::available_parallelism().map_or(1, |cores| cores.get().min(MAX_THREADS)),
+    };
+    let mut computer = ComputerPlayer::new(colour, threads);
+    if let Some(i) = args.iter().position(|arg| arg == "--eval") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--eval needs a parameter file after it");
+            std::process::exit(1);
+        };
+        match load_params(path) {
+            Ok(params) => computer.options.params = Arc::new(params),
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--book") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--book needs a Polyglot book file after it");
+            std::process::exit(1);
+        };
+        match load_book(path) {
+            Ok(book) => computer.options.book = Some(Arc::new(book)),
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+        }
+    }
+    Some(computer)
+}
+
+// `cargo run -- --endgames <file>` loads tables from the endgames binary: H then hints the best move
+// in those endings, and the computer plays them perfectly
+fn endgame_hint() -> Option<EndgameHint> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|arg| arg == "--endgames")?;
+    let Some(path) = args.get(i + 1) else {
+        eprintln!("--endgames needs an endgame table file after it");
+        std::process::exit(1);
+    };
+    match load_endgames(path) {
+        Ok(endgames) => Some(EndgameHint(Arc::new(endgames))),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
 }
 
-// also have to somehow check that the king is not in check --- TBD
\ No newline at end of file
+// `cargo run -- --fen "<FEN>"` starts from that position instead of the usual one
+fn starting_board() -> GameBoard {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(i) = args.iter().position(|arg| arg == "--fen") else {
+        return GameBoard::default();
+    };
+    let Some(fen) = args.get(i + 1) else {
+        eprintln!("--fen needs a FEN string after it");
+        std::process::exit(1);
+    };
+    match ArrayBoard::from_fen(fen) {
+        Ok(array_board) => GameBoard(array_board),
+        Err(error) => {
+            eprintln!("invalid FEN \"{fen}\": {error}");
+            std::process::exit(1);
+        }
+    }
+}
\ No newline at end of file